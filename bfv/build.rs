@@ -1,5 +1,25 @@
 fn main() -> std::io::Result<()> {
-    #[cfg(feature = "serialize")]
-    prost_build::compile_protos(&["src/proto/bfv.proto"], &["src/proto"])?;
+    // Generate protobuf bindings along with hash of `bfv.proto` they were generated from into OUT_DIR.
+    // `checked_in_protos_match_generated` test compares them byte for byte with checked-in `bfv.rs`.
+    #[cfg(feature = "build-protos")]
+    {
+        use sha2::{Digest, Sha256};
+        use std::{env, fs, path::Path};
+
+        println!("cargo:rerun-if-changed=src/proto/bfv.proto");
+        prost_build::compile_protos(&["src/proto/bfv.proto"], &["src/proto"])?;
+
+        let out_dir = env::var("OUT_DIR").unwrap();
+        let generated = fs::read_to_string(Path::new(&out_dir).join("_.rs"))?;
+        let hash = Sha256::digest(fs::read("src/proto/bfv.proto")?);
+        fs::write(
+            Path::new(&out_dir).join("bfv.rs"),
+            format!(
+                "// @generated by prost-build from bfv.proto. Do not edit.\n\
+                 // Regenerate by copying `bfv.rs` generated in OUT_DIR with `build-protos` feature.\n\
+                 // bfv.proto sha256: {hash:x}\n\n{generated}"
+            ),
+        )?;
+    }
     Ok(())
 }
@@ -1,23 +1,45 @@
-use crate::{BfvParameters, Poly, PolyType};
-use itertools::Itertools;
+use crate::{
+    BfvParameters, EncodingType, KeySwitchingKey, KeySwitchingMethod, NoiseEstimate, Poly,
+    PolyType, Representation,
+};
+use itertools::{izip, Itertools};
 use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct Ciphertext {
     pub(crate) c: Vec<Poly>,
     pub(crate) poly_type: PolyType,
     pub(crate) seed: Option<<ChaCha8Rng as SeedableRng>::Seed>,
     pub(crate) level: usize,
+    /// Encoding type of encrypted plaintext. Recorded in plaintext after decryption.
+    pub(crate) encoding_type: EncodingType,
+    /// Heuristic noise estimate updated by every operation. Missing if ciphertext was not output of
+    /// encryption or of operations on ciphertexts with noise estimates (for ex, if it was deserialized).
+    pub(crate) noise: Option<NoiseEstimate>,
+}
+
+/// Noise estimate is heuristic, hence it is ignored by equality
+impl PartialEq for Ciphertext {
+    fn eq(&self, other: &Self) -> bool {
+        self.c == other.c
+            && self.poly_type == other.poly_type
+            && self.seed == other.seed
+            && self.level == other.level
+            && self.encoding_type == other.encoding_type
+    }
 }
 
 impl Ciphertext {
+    /// Returns ciphertext of SIMD encoded plaintext
     pub fn new(c: Vec<Poly>, poly_type: PolyType, level: usize) -> Ciphertext {
         Ciphertext {
             c,
             poly_type,
             level,
             seed: None,
+            encoding_type: EncodingType::Simd,
+            noise: None,
         }
     }
 
@@ -27,10 +49,16 @@ impl Ciphertext {
             poly_type: PolyType::Q,
             level: 0,
             seed: None,
+            encoding_type: EncodingType::Simd,
+            noise: None,
         }
     }
 
+    /// Returns mutable reference to polynomials.
+    ///
+    /// Clears seed since `c[1]` may no longer match it after modification.
     pub fn c_ref_mut(&mut self) -> &mut [Poly] {
+        self.seed = None;
         &mut self.c
     }
 
@@ -45,6 +73,93 @@ impl Ciphertext {
     pub fn level(&self) -> usize {
         self.level
     }
+
+    pub fn encoding_type(&self) -> EncodingType {
+        self.encoding_type.clone()
+    }
+
+    /// Returns heuristic noise estimate (see `NoiseEstimate`)
+    pub fn noise_estimate(&self) -> Option<NoiseEstimate> {
+        self.noise
+    }
+
+    /// Returns true if ciphertext has a seed and `c[1]` (in `Coefficient` representation) equals
+    /// polynomial sampled from the seed. Only such ciphertexts can be serialized without `c[1]`.
+    pub fn has_valid_seed(&self, params: &BfvParameters) -> bool {
+        match self.seed {
+            Some(seed)
+                if self.c.len() == 2
+                    && self.poly_type == PolyType::Q
+                    && self.c[1].representation == Representation::Coefficient =>
+            {
+                self.c[1]
+                    == params
+                        .poly_ctx(&PolyType::Q, self.level)
+                        .random_with_seed(seed)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Ciphertext with key switched polynomials kept in QP, ie ciphertext (c_0 + [d_0/P], c_1 + [d_1/P]), where
+/// c_i are in Q and d_i are in QP (in Q for BV key switching, in which case P = 1).
+///
+/// Output of key switching operations that skip mod down (for ex, `Evaluator::rotate_qp`). Sums of such
+/// ciphertexts are accumulated in QP with `Evaluator::add_assign_qp` and switched to `Ciphertext` with a single
+/// mod down per polynomial using `Evaluator::mod_down_qp`.
+#[derive(Debug, Clone)]
+pub struct QpCiphertext {
+    /// c_0 and c_1 in Q
+    pub(crate) c: Vec<Poly>,
+    /// d_0 and d_1 in `Evaluation` representation in context of `KeySwitchingKey::decomposition_ctx`
+    pub(crate) d: Vec<Poly>,
+    pub(crate) level: usize,
+    /// Key switching method of the key that output d_i. Determines their context and mod down.
+    pub(crate) method: KeySwitchingMethod,
+    pub(crate) encoding_type: EncodingType,
+    pub(crate) noise: Option<NoiseEstimate>,
+}
+
+impl QpCiphertext {
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    pub fn encoding_type(&self) -> EncodingType {
+        self.encoding_type.clone()
+    }
+
+    pub fn key_switching_method(&self) -> KeySwitchingMethod {
+        self.method
+    }
+
+    /// Returns heuristic noise estimate after mod down (see `NoiseEstimate`)
+    pub fn noise_estimate(&self) -> Option<NoiseEstimate> {
+        self.noise
+    }
+
+    /// Switches d_i to Q and adds them to c_i. Output is in representation of c_i.
+    pub(crate) fn mod_down(self, params: &BfvParameters) -> Ciphertext {
+        let q_ctx = params.poly_ctx(&PolyType::Q, self.level);
+        let c = izip!(self.c.into_iter(), self.d.into_iter())
+            .map(|(mut c, d)| {
+                let mut d = KeySwitchingKey::mod_down(params, self.method, d, self.level);
+                q_ctx.change_representation(&mut d, c.representation.clone());
+                q_ctx.add_assign(&mut c, &d);
+                c
+            })
+            .collect_vec();
+
+        Ciphertext {
+            c,
+            poly_type: PolyType::Q,
+            level: self.level,
+            seed: None,
+            encoding_type: self.encoding_type,
+            noise: self.noise,
+        }
+    }
 }
 
 mod tests {
@@ -0,0 +1,372 @@
+use crate::nb_theory::gcd;
+use crate::{mod_inverse_biguint_u64, BfvError, BfvParameters, Ciphertext, Encoding};
+use crate::{EvaluationKey, Evaluator, Plaintext, PublicKey, SecretKey};
+use itertools::{izip, Itertools};
+use num_bigint::BigUint;
+use num_traits::{ToPrimitive, Zero};
+use rand::{CryptoRng, RngCore};
+
+/// Plaintext encoded under each plaintext modulus of `CrtEvaluator`
+#[derive(Clone)]
+pub struct CrtPlaintext {
+    pub(crate) pts: Vec<Plaintext>,
+}
+
+impl CrtPlaintext {
+    pub fn components(&self) -> &[Plaintext] {
+        &self.pts
+    }
+}
+
+/// Ciphertext encrypted under each plaintext modulus of `CrtEvaluator`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrtCiphertext {
+    pub(crate) cts: Vec<Ciphertext>,
+}
+
+impl CrtCiphertext {
+    pub fn components(&self) -> &[Ciphertext] {
+        &self.cts
+    }
+
+    pub fn level(&self) -> usize {
+        self.cts[0].level
+    }
+}
+
+/// Evaluator for plaintexts with modulus T = t_0 * t_1 * ... * t_{k-1} larger than a single plaintext modulus.
+///
+/// Holds an `Evaluator` per plaintext modulus t_i. All evaluators share ciphertext moduli, hence the same
+/// `SecretKey`, `PublicKey`, and `EvaluationKey` work for all of them. Messages are encoded by CRT across
+/// plaintext moduli, operations are applied component-wise, and results are reconstructed modulo T on decoding.
+pub struct CrtEvaluator {
+    evaluators: Vec<Evaluator>,
+    // T
+    plaintext_modulus: BigUint,
+    // T/t_i
+    t_hat: Vec<BigUint>,
+    // [(T/t_i)^{-1}]_t_i
+    t_hat_inv_modt: Vec<BigUint>,
+}
+
+impl CrtEvaluator {
+    /// Panics if plaintext moduli are invalid. Check `try_new` for requirements.
+    pub fn new(params: &BfvParameters, plaintext_moduli: &[u64]) -> CrtEvaluator {
+        CrtEvaluator::try_new(params, plaintext_moduli).unwrap()
+    }
+
+    /// Creates evaluator with parameters that have same ciphertext moduli as `params` for each of
+    /// `plaintext_moduli`
+    ///
+    /// Returns error if `plaintext_moduli` is empty, plaintext moduli are not pairwise coprime, or any
+    /// plaintext modulus is not coprime to Q or does not support SIMD encoding (ie it is not a prime = 1 mod 2N).
+    pub fn try_new(
+        params: &BfvParameters,
+        plaintext_moduli: &[u64],
+    ) -> Result<CrtEvaluator, BfvError> {
+        if plaintext_moduli.is_empty() {
+            return Err(BfvError::MissingPlaintextModuli);
+        }
+
+        for (i, a) in plaintext_moduli.iter().enumerate() {
+            for b in plaintext_moduli[i + 1..].iter() {
+                if gcd(*a, *b) != 1 {
+                    return Err(BfvError::ModuliNotCoprime { a: *a, b: *b });
+                }
+            }
+        }
+
+        let evaluators = plaintext_moduli
+            .iter()
+            .map(|t| {
+                let params = params.try_with_plaintext_modulus(*t)?;
+                if !params.supports_batching() {
+                    return Err(BfvError::BatchingNotSupported {
+                        plaintext_modulus: *t,
+                        degree: params.degree,
+                    });
+                }
+                Ok(Evaluator::new(params))
+            })
+            .collect::<Result<Vec<_>, BfvError>>()?;
+
+        let plaintext_modulus = plaintext_moduli
+            .iter()
+            .fold(BigUint::from(1u64), |acc, t| acc * t);
+        let t_hat = plaintext_moduli
+            .iter()
+            .map(|t| &plaintext_modulus / t)
+            .collect_vec();
+        let t_hat_inv_modt = izip!(t_hat.iter(), plaintext_moduli.iter())
+            .map(|(t_hat, t)| mod_inverse_biguint_u64(t_hat, *t))
+            .collect_vec();
+
+        Ok(CrtEvaluator {
+            evaluators,
+            plaintext_modulus,
+            t_hat,
+            t_hat_inv_modt,
+        })
+    }
+
+    pub fn evaluators(&self) -> &[Evaluator] {
+        &self.evaluators
+    }
+
+    /// Returns T, product of all plaintext moduli
+    pub fn plaintext_modulus(&self) -> &BigUint {
+        &self.plaintext_modulus
+    }
+
+    pub fn encode(&self, m: &[u128], encoding: Encoding) -> CrtPlaintext {
+        let m = m.iter().map(|v| BigUint::from(*v)).collect_vec();
+        self.encode_biguint(&m, encoding)
+    }
+
+    /// Encodes values modulo T. Values greater than T are reduced.
+    pub fn encode_biguint(&self, m: &[BigUint], encoding: Encoding) -> CrtPlaintext {
+        let pts = self
+            .evaluators
+            .iter()
+            .map(|evaluator| {
+                let t = evaluator.params.plaintext_modulus;
+                let m_modt = m.iter().map(|v| (v % t).to_u64().unwrap()).collect_vec();
+                evaluator.plaintext_encode(&m_modt, encoding.clone())
+            })
+            .collect_vec();
+        CrtPlaintext { pts }
+    }
+
+    /// Returns decoded values in [0, T)
+    pub fn decode(&self, pt: &CrtPlaintext, encoding: Encoding) -> Vec<BigUint> {
+        let m_modt = izip!(self.evaluators.iter(), pt.pts.iter())
+            .map(|(evaluator, pt)| evaluator.plaintext_decode(pt, encoding.clone()))
+            .collect_vec();
+
+        // CRT reconstruction: m = \sum [m_i * (T/t_i)^{-1}]_t_i * (T/t_i) mod T
+        (0..m_modt[0].len())
+            .map(|j| {
+                let mut m = BigUint::zero();
+                izip!(
+                    self.evaluators.iter(),
+                    m_modt.iter(),
+                    self.t_hat.iter(),
+                    self.t_hat_inv_modt.iter()
+                )
+                .for_each(|(evaluator, m_i, t_hat, t_hat_inv)| {
+                    m += ((m_i[j] * t_hat_inv) % evaluator.params.plaintext_modulus) * t_hat;
+                });
+                m % &self.plaintext_modulus
+            })
+            .collect()
+    }
+
+    /// Same as `decode` but panics if any value does not fit in u128
+    pub fn decode_u128(&self, pt: &CrtPlaintext, encoding: Encoding) -> Vec<u128> {
+        self.decode(pt, encoding)
+            .iter()
+            .map(|v| v.to_u128().unwrap())
+            .collect()
+    }
+
+    pub fn encrypt<R: RngCore + CryptoRng>(
+        &self,
+        sk: &SecretKey,
+        pt: &CrtPlaintext,
+        rng: &mut R,
+    ) -> CrtCiphertext {
+        let cts = izip!(self.evaluators.iter(), pt.pts.iter())
+            .map(|(evaluator, pt)| evaluator.encrypt(sk, pt, rng))
+            .collect_vec();
+        CrtCiphertext { cts }
+    }
+
+    pub fn encrypt_public<R: RngCore + CryptoRng>(
+        &self,
+        pk: &PublicKey,
+        pt: &CrtPlaintext,
+        rng: &mut R,
+    ) -> CrtCiphertext {
+        let cts = izip!(self.evaluators.iter(), pt.pts.iter())
+            .map(|(evaluator, pt)| evaluator.encrypt_public(pk, pt, rng))
+            .collect_vec();
+        CrtCiphertext { cts }
+    }
+
+    pub fn decrypt(&self, sk: &SecretKey, ct: &CrtCiphertext) -> CrtPlaintext {
+        let pts = izip!(self.evaluators.iter(), ct.cts.iter())
+            .map(|(evaluator, ct)| evaluator.decrypt(sk, ct))
+            .collect_vec();
+        CrtPlaintext { pts }
+    }
+
+    /// Returns max. noise across components
+    pub fn measure_noise(&self, sk: &SecretKey, ct: &CrtCiphertext) -> u64 {
+        izip!(self.evaluators.iter(), ct.cts.iter())
+            .map(|(evaluator, ct)| evaluator.measure_noise(sk, ct))
+            .max()
+            .unwrap()
+    }
+
+    pub fn add(&self, c0: &CrtCiphertext, c1: &CrtCiphertext) -> CrtCiphertext {
+        self.zip_map(c0, c1, |evaluator, c0, c1| evaluator.add(c0, c1))
+    }
+
+    pub fn sub(&self, c0: &CrtCiphertext, c1: &CrtCiphertext) -> CrtCiphertext {
+        self.zip_map(c0, c1, |evaluator, c0, c1| evaluator.sub(c0, c1))
+    }
+
+    pub fn mul(&self, c0: &CrtCiphertext, c1: &CrtCiphertext) -> CrtCiphertext {
+        self.zip_map(c0, c1, |evaluator, c0, c1| evaluator.mul(c0, c1))
+    }
+
+    pub fn negate(&self, c0: &CrtCiphertext) -> CrtCiphertext {
+        self.map(c0, |evaluator, c0| evaluator.negate(c0))
+    }
+
+    pub fn relinearize(&self, c0: &CrtCiphertext, ek: &EvaluationKey) -> CrtCiphertext {
+        self.map(c0, |evaluator, c0| evaluator.relinearize(c0, ek))
+    }
+
+    pub fn rotate(
+        &self,
+        c0: &CrtCiphertext,
+        rotate_by: isize,
+        ek: &EvaluationKey,
+    ) -> CrtCiphertext {
+        self.map(c0, |evaluator, c0| evaluator.rotate(c0, rotate_by, ek))
+    }
+
+    pub fn mod_down_next(&self, c0: &mut CrtCiphertext) {
+        izip!(self.evaluators.iter(), c0.cts.iter_mut())
+            .for_each(|(evaluator, c0)| evaluator.mod_down_next(c0));
+    }
+
+    pub fn add_plaintext(&self, ct: &CrtCiphertext, pt: &CrtPlaintext) -> CrtCiphertext {
+        let cts = izip!(self.evaluators.iter(), ct.cts.iter(), pt.pts.iter())
+            .map(|(evaluator, ct, pt)| evaluator.add_plaintext(ct, pt))
+            .collect_vec();
+        CrtCiphertext { cts }
+    }
+
+    pub fn sub_plaintext(&self, ct: &CrtCiphertext, pt: &CrtPlaintext) -> CrtCiphertext {
+        let cts = izip!(self.evaluators.iter(), ct.cts.iter(), pt.pts.iter())
+            .map(|(evaluator, ct, pt)| evaluator.sub_plaintext(ct, pt))
+            .collect_vec();
+        CrtCiphertext { cts }
+    }
+
+    pub fn mul_plaintext(&self, ct: &CrtCiphertext, pt: &CrtPlaintext) -> CrtCiphertext {
+        let cts = izip!(self.evaluators.iter(), ct.cts.iter(), pt.pts.iter())
+            .map(|(evaluator, ct, pt)| evaluator.mul_plaintext(ct, pt))
+            .collect_vec();
+        CrtCiphertext { cts }
+    }
+
+    /// Applies `f` to each component of `c0` with its evaluator
+    pub fn map<F: Fn(&Evaluator, &Ciphertext) -> Ciphertext>(
+        &self,
+        c0: &CrtCiphertext,
+        f: F,
+    ) -> CrtCiphertext {
+        let cts = izip!(self.evaluators.iter(), c0.cts.iter())
+            .map(|(evaluator, c0)| f(evaluator, c0))
+            .collect_vec();
+        CrtCiphertext { cts }
+    }
+
+    /// Applies `f` to each pair of components of `c0` and `c1` with their evaluator
+    pub fn zip_map<F: Fn(&Evaluator, &Ciphertext, &Ciphertext) -> Ciphertext>(
+        &self,
+        c0: &CrtCiphertext,
+        c1: &CrtCiphertext,
+        f: F,
+    ) -> CrtCiphertext {
+        let cts = izip!(self.evaluators.iter(), c0.cts.iter(), c1.cts.iter())
+            .map(|(evaluator, c0, c1)| f(evaluator, c0, c1))
+            .collect_vec();
+        CrtCiphertext { cts }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_primes_vec;
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn crt_mul_add() {
+        let mut rng = thread_rng();
+        let params = BfvParameters::default(5, 1 << 4);
+
+        // three 17 bits batching primes, T ~ 2^51
+        let plaintext_moduli = generate_primes_vec(&[17, 17, 17], params.degree, &[]);
+        let crt_evaluator = CrtEvaluator::new(&params, &plaintext_moduli);
+        let big_t = crt_evaluator.plaintext_modulus().clone();
+
+        // gen keys
+        let sk = SecretKey::random(params.degree, params.hw, &mut rng);
+        let ek = EvaluationKey::new(&params, &sk, &[0], &[], &[], &mut rng);
+
+        let bound = big_t.to_u128().unwrap();
+        let m0 = (0..params.degree)
+            .map(|_| rng.gen_range(0..bound))
+            .collect_vec();
+        let m1 = (0..params.degree)
+            .map(|_| rng.gen_range(0..bound))
+            .collect_vec();
+
+        let ct0 = crt_evaluator.encrypt(
+            &sk,
+            &crt_evaluator.encode(&m0, Encoding::default()),
+            &mut rng,
+        );
+        let ct1 = crt_evaluator.encrypt(
+            &sk,
+            &crt_evaluator.encode(&m1, Encoding::default()),
+            &mut rng,
+        );
+
+        // m0 * m1 + m0
+        let ct01 = crt_evaluator.relinearize(&crt_evaluator.mul(&ct0, &ct1), &ek);
+        let ct = crt_evaluator.add(&ct01, &ct0);
+        izip!(crt_evaluator.evaluators(), ct.components())
+            .for_each(|(evaluator, ct)| assert!(evaluator.noise_budget(&sk, ct) > 0));
+
+        let res = crt_evaluator.decode(&crt_evaluator.decrypt(&sk, &ct), Encoding::default());
+        let expected = izip!(m0.iter(), m1.iter())
+            .map(|(a, b)| (BigUint::from(*a) * b + a) % &big_t)
+            .collect_vec();
+        assert_eq!(res, expected);
+
+        // plaintext moduli must be coprime
+        assert!(CrtEvaluator::try_new(&params, &[65537, 65537]).is_err());
+    }
+
+    #[test]
+    fn crt_evaluator_rejects_invalid_plaintext_moduli() {
+        let params = BfvParameters::default(5, 1 << 4);
+
+        assert!(matches!(
+            CrtEvaluator::try_new(&params, &[]),
+            Err(BfvError::MissingPlaintextModuli)
+        ));
+
+        // coprime moduli that do not support batching
+        assert!(matches!(
+            CrtEvaluator::try_new(&params, &[4, 9]),
+            Err(BfvError::BatchingNotSupported {
+                plaintext_modulus: 4,
+                degree: 16
+            })
+        ));
+        assert!(matches!(
+            CrtEvaluator::try_new(&params, &[65537, 65539]),
+            Err(BfvError::BatchingNotSupported {
+                plaintext_modulus: 65539,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,262 @@
+use crate::{EncodingType, KeySwitchingMethod, PolyType, Representation, SecurityLevel};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BfvError {
+    /// Operands are at different levels
+    LevelMismatch { expected: usize, found: usize },
+    /// Operation is not supported at the given level
+    InvalidLevel { level: usize, max_level: usize },
+    /// Polynomials of the operands are in different representations
+    RepresentationMismatch {
+        expected: Representation,
+        found: Representation,
+    },
+    /// Operands are in different (or unsupported) polynomial contexts
+    PolyTypeMismatch { expected: PolyType, found: PolyType },
+    /// Ciphertext does not have the required no. of polynomials
+    CiphertextSizeMismatch { expected: usize, found: usize },
+    /// `EvaluationKey` does not have relinearization key for the level
+    MissingRelinearizationKey { level: usize },
+    /// Relinearization key cannot relinearize ciphertext of the degree
+    RelinearizationKeyDegreeTooSmall { max_degree: usize, degree: usize },
+    /// `EvaluationKey` does not have galois key for the rotation and level
+    MissingGaloisKey { rotate_by: isize, level: usize },
+    /// Operands were key switched with keys of different key switching methods
+    KeySwitchingMethodMismatch {
+        expected: KeySwitchingMethod,
+        found: KeySwitchingMethod,
+    },
+    /// Width of slots to sum is not a power of two or exceeds no. of slots
+    InvalidSumWidth { width: usize, degree: usize },
+    /// Matrix of linear transform is not square with dimension that is a power of two <= N/2
+    InvalidMatrixShape {
+        rows: usize,
+        cols: usize,
+        degree: usize,
+    },
+    /// Diagonal of linear transform has index >= dimension or its length is not the dimension
+    InvalidDiagonal {
+        index: usize,
+        len: usize,
+        dim: usize,
+    },
+    /// Message has more values than polynomial degree
+    MessageTooLong { len: usize, degree: usize },
+    /// Plaintext was not encoded (for ex, it was output of decryption)
+    MissingEncoding,
+    /// Plaintext is decoded with different encoding type than the one it was encoded with
+    EncodingMismatch {
+        expected: EncodingType,
+        found: EncodingType,
+    },
+    /// Plaintext modulus does not support SIMD encoding (ie it is not a prime = 1 mod 2N)
+    BatchingNotSupported {
+        plaintext_modulus: u64,
+        degree: usize,
+    },
+    /// No plaintext moduli are given for `CrtEvaluator`
+    MissingPlaintextModuli,
+    /// No ciphertext moduli sizes are given for `BfvParametersBuilder`
+    MissingCiphertextModuli,
+    /// Plaintext was not encoded with `PolyCache` that supports multiplication
+    MissingMulPoly,
+    /// Plaintext was not encoded with `PolyCache` that supports additions and subtractions
+    MissingAddSubPoly,
+    /// log(QP) exceeds bound for target security level at degree (or degree is not in HE standard tables)
+    InsecureParameters {
+        degree: usize,
+        log_qp: usize,
+        security_level: SecurityLevel,
+    },
+    /// Secret is sparser than uniform ternary secret assumed by HE standard tables
+    InsecureSecretDistribution {
+        hamming_weight: usize,
+        degree: usize,
+    },
+    /// Degree is not a power of two >= 16
+    InvalidDegree { degree: usize },
+    /// Modulus is not 1 mod 2N
+    ModulusNotNttFriendly { modulus: u64, degree: usize },
+    /// Moduli are not coprime
+    ModuliNotCoprime { a: u64, b: u64 },
+    /// No. of moduli in a basis does not match the required count
+    ModuliCountMismatch { expected: usize, found: usize },
+    /// Extension basis P is smaller than ciphertext basis Q at the level
+    ExtensionBasisTooSmall { level: usize },
+    /// Alpha is 0 or special moduli are missing
+    InvalidDecomposition { alpha: usize, moduli_count: usize },
+    /// Serialized bytes are shorter than required
+    TruncatedBytes { expected: usize, found: usize },
+    /// Serialized bytes do not start with the magic
+    InvalidMagic,
+    /// Serialized bytes use an unsupported version of the wire format
+    UnsupportedVersion { version: u16 },
+    /// Serialized bytes store a different type of object (or an unknown tag). Tags are `ObjectType as u8`
+    ObjectTypeMismatch { expected: u8, found: u8 },
+    /// Serialized object was created with different parameters
+    ParametersMismatch,
+    /// Checksum of serialized bytes does not match
+    ChecksumMismatch,
+    /// Serialized object is malformed or does not match parameters
+    InvalidObject { reason: String },
+}
+
+impl fmt::Display for BfvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BfvError::LevelMismatch { expected, found } => {
+                write!(f, "Level mismatch: expected {expected}, found {found}")
+            }
+            BfvError::InvalidLevel { level, max_level } => {
+                write!(f, "Invalid level {level} for max level {max_level}")
+            }
+            BfvError::RepresentationMismatch { expected, found } => {
+                write!(
+                    f,
+                    "Representation mismatch: expected {expected:?}, found {found:?}"
+                )
+            }
+            BfvError::PolyTypeMismatch { expected, found } => {
+                write!(
+                    f,
+                    "PolyType mismatch: expected {expected:?}, found {found:?}"
+                )
+            }
+            BfvError::CiphertextSizeMismatch { expected, found } => {
+                write!(
+                    f,
+                    "Ciphertext size mismatch: expected {expected} polynomials, found {found}"
+                )
+            }
+            BfvError::MissingRelinearizationKey { level } => {
+                write!(f, "Rlk missing for level {level}")
+            }
+            BfvError::RelinearizationKeyDegreeTooSmall { max_degree, degree } => {
+                write!(
+                    f,
+                    "Rlk with max degree {max_degree} cannot relinearize ciphertext of degree {degree}"
+                )
+            }
+            BfvError::MissingGaloisKey { rotate_by, level } => {
+                write!(f, "Rtg missing for rotation {rotate_by} at level {level}")
+            }
+            BfvError::KeySwitchingMethodMismatch { expected, found } => {
+                write!(
+                    f,
+                    "Key switching method mismatch: expected {expected:?}, found {found:?}"
+                )
+            }
+            BfvError::InvalidSumWidth { width, degree } => {
+                write!(
+                    f,
+                    "Invalid width {width} to sum slots for degree {degree}: width must be a power of two <= degree"
+                )
+            }
+            BfvError::InvalidMatrixShape { rows, cols, degree } => {
+                write!(
+                    f,
+                    "Invalid {rows}x{cols} matrix for degree {degree}: matrix must be square with dimension a power of two <= degree/2"
+                )
+            }
+            BfvError::InvalidDiagonal { index, len, dim } => {
+                write!(
+                    f,
+                    "Invalid diagonal {index} of length {len} for matrix of dimension {dim}"
+                )
+            }
+            BfvError::MessageTooLong { len, degree } => {
+                write!(f, "Message of length {len} exceeds degree {degree}")
+            }
+            BfvError::MissingEncoding => write!(f, "Plaintext encoding missing"),
+            BfvError::EncodingMismatch { expected, found } => {
+                write!(
+                    f,
+                    "Encoding mismatch: expected {expected:?}, found {found:?}"
+                )
+            }
+            BfvError::BatchingNotSupported {
+                plaintext_modulus,
+                degree,
+            } => {
+                write!(
+                    f,
+                    "Plaintext modulus {plaintext_modulus} does not support SIMD encoding for degree {degree}: it must be a prime = 1 mod {}",
+                    2 * degree
+                )
+            }
+            BfvError::MissingPlaintextModuli => write!(f, "Plaintext moduli missing"),
+            BfvError::MissingCiphertextModuli => write!(f, "Ciphertext moduli missing"),
+            BfvError::MissingMulPoly => write!(f, "Plaintext missing mul poly"),
+            BfvError::MissingAddSubPoly => write!(f, "Plaintext missing add_sub poly"),
+            BfvError::InsecureParameters {
+                degree,
+                log_qp,
+                security_level,
+            } => {
+                write!(
+                    f,
+                    "log(QP) of {log_qp} bits is insecure for degree {degree} at {security_level:?}"
+                )
+            }
+            BfvError::InsecureSecretDistribution {
+                hamming_weight,
+                degree,
+            } => {
+                write!(
+                    f,
+                    "Secret with hamming weight {hamming_weight} is insecure for degree {degree}"
+                )
+            }
+            BfvError::InvalidDegree { degree } => {
+                write!(f, "Degree {degree} is not a power of two >= 16")
+            }
+            BfvError::ModulusNotNttFriendly { modulus, degree } => {
+                write!(
+                    f,
+                    "Modulus {modulus} is not NTT friendly for degree {degree}"
+                )
+            }
+            BfvError::ModuliNotCoprime { a, b } => write!(f, "Moduli {a} and {b} are not coprime"),
+            BfvError::ModuliCountMismatch { expected, found } => {
+                write!(
+                    f,
+                    "Moduli count mismatch: expected {expected}, found {found}"
+                )
+            }
+            BfvError::ExtensionBasisTooSmall { level } => {
+                write!(f, "Extension basis is too small at level {level}")
+            }
+            BfvError::InvalidDecomposition {
+                alpha,
+                moduli_count,
+            } => {
+                write!(
+                    f,
+                    "Invalid decomposition with alpha {alpha} for {moduli_count} moduli"
+                )
+            }
+            BfvError::TruncatedBytes { expected, found } => {
+                write!(
+                    f,
+                    "Truncated bytes: expected at least {expected} bytes, found {found}"
+                )
+            }
+            BfvError::InvalidMagic => write!(f, "Invalid magic"),
+            BfvError::UnsupportedVersion { version } => {
+                write!(f, "Unsupported wire format version {version}")
+            }
+            BfvError::ObjectTypeMismatch { expected, found } => {
+                write!(
+                    f,
+                    "Object type mismatch: expected tag {expected}, found tag {found}"
+                )
+            }
+            BfvError::ParametersMismatch => write!(f, "Parameters fingerprint mismatch"),
+            BfvError::ChecksumMismatch => write!(f, "Checksum mismatch"),
+            BfvError::InvalidObject { reason } => write!(f, "Invalid object: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for BfvError {}
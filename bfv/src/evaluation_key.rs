@@ -1,4 +1,7 @@
-use crate::{rot_to_galois_element, BfvParameters, GaloisKey, RelinearizationKey, SecretKey};
+use crate::{
+    power_of_two_rotations, rot_to_galois_element, sum_slots_rotations, BfvError, BfvParameters,
+    GaloisKey, LinearTransform, RelinearizationKey, SecretKey,
+};
 use itertools::{izip, Itertools};
 use rand::{CryptoRng, RngCore};
 use std::collections::HashMap;
@@ -17,12 +20,37 @@ impl EvaluationKey {
         rtg_levels: &[usize],
         rtg_indices: &[isize],
         rng: &mut R,
+    ) -> EvaluationKey {
+        EvaluationKey::new_with_rlk_max_degree(
+            params,
+            sk,
+            rlk_levels,
+            2,
+            rtg_levels,
+            rtg_indices,
+            rng,
+        )
+    }
+
+    /// Same as `new` but relinearization keys at `rlk_levels` can relinearize ciphertexts with upto
+    /// `rlk_max_degree + 1` polynomials
+    pub fn new_with_rlk_max_degree<R: CryptoRng + RngCore>(
+        params: &BfvParameters,
+        sk: &SecretKey,
+        rlk_levels: &[usize],
+        rlk_max_degree: usize,
+        rtg_levels: &[usize],
+        rtg_indices: &[isize],
+        rng: &mut R,
     ) -> EvaluationKey {
         assert!(rtg_levels.len() == rtg_indices.len());
 
         let mut rlks = HashMap::new();
         rlk_levels.iter().for_each(|l| {
-            rlks.insert(*l, RelinearizationKey::new(params, sk, *l, rng));
+            rlks.insert(
+                *l,
+                RelinearizationKey::new_with_max_degree(params, sk, *l, rlk_max_degree, rng),
+            );
         });
 
         let mut rtgs = HashMap::new();
@@ -43,9 +71,127 @@ impl EvaluationKey {
         EvaluationKey { rlks, rtgs }
     }
 
+    /// Generates evaluation key with galois keys only for power-of-two rotations (see `power_of_two_rotations`)
+    /// and row swap at each of `rtg_levels`.
+    ///
+    /// `Evaluator::rotate` decomposes rotations for which galois key is missing into power-of-two rotations. This
+    /// requires far fewer keys at the cost of extra key switches per rotation.
+    pub fn new_with_power_of_two_rotations<R: CryptoRng + RngCore>(
+        params: &BfvParameters,
+        sk: &SecretKey,
+        rlk_levels: &[usize],
+        rtg_levels: &[usize],
+        rng: &mut R,
+    ) -> EvaluationKey {
+        let mut indices = power_of_two_rotations(params.degree);
+        // row swap
+        indices.push((2 * params.degree - 1) as isize);
+
+        EvaluationKey::new_with_rotations_at_levels(
+            params, sk, rlk_levels, rtg_levels, &indices, rng,
+        )
+    }
+
+    /// Generates evaluation key with exactly the galois keys required by `Evaluator::sum_slots` with `width`
+    /// (see `sum_slots_rotations`) at each of `rtg_levels`. For `Evaluator::inner_sum` use `width` = N.
+    ///
+    /// Panics if `width` is not a power of two or exceeds N.
+    pub fn new_with_sum_slots_rotations<R: CryptoRng + RngCore>(
+        params: &BfvParameters,
+        sk: &SecretKey,
+        rlk_levels: &[usize],
+        rtg_levels: &[usize],
+        width: usize,
+        rng: &mut R,
+    ) -> EvaluationKey {
+        EvaluationKey::try_new_with_sum_slots_rotations(
+            params, sk, rlk_levels, rtg_levels, width, rng,
+        )
+        .unwrap()
+    }
+
+    /// Generates evaluation key with exactly the galois keys required by `Evaluator::sum_slots` with `width`
+    /// (see `sum_slots_rotations`) at each of `rtg_levels`. For `Evaluator::inner_sum` use `width` = N.
+    ///
+    /// Returns error if `width` is not a power of two or exceeds N.
+    pub fn try_new_with_sum_slots_rotations<R: CryptoRng + RngCore>(
+        params: &BfvParameters,
+        sk: &SecretKey,
+        rlk_levels: &[usize],
+        rtg_levels: &[usize],
+        width: usize,
+        rng: &mut R,
+    ) -> Result<EvaluationKey, BfvError> {
+        if !width.is_power_of_two() || width > params.degree {
+            return Err(BfvError::InvalidSumWidth {
+                width,
+                degree: params.degree,
+            });
+        }
+        let indices = sum_slots_rotations(params.degree, width);
+        Ok(EvaluationKey::new_with_rotations_at_levels(
+            params, sk, rlk_levels, rtg_levels, &indices, rng,
+        ))
+    }
+
+    /// Generates evaluation key with exactly the galois keys required to evaluate `lt` (see
+    /// `LinearTransform::rotations`) at its level
+    pub fn new_with_linear_transform_rotations<R: CryptoRng + RngCore>(
+        params: &BfvParameters,
+        sk: &SecretKey,
+        rlk_levels: &[usize],
+        lt: &LinearTransform,
+        rng: &mut R,
+    ) -> EvaluationKey {
+        EvaluationKey::new_with_rotations_at_levels(
+            params,
+            sk,
+            rlk_levels,
+            &[lt.level()],
+            &lt.rotations(),
+            rng,
+        )
+    }
+
+    /// Generates galois keys for each of `indices` at each of `rtg_levels`
+    fn new_with_rotations_at_levels<R: CryptoRng + RngCore>(
+        params: &BfvParameters,
+        sk: &SecretKey,
+        rlk_levels: &[usize],
+        rtg_levels: &[usize],
+        indices: &[isize],
+        rng: &mut R,
+    ) -> EvaluationKey {
+        let rtg_indices = rtg_levels
+            .iter()
+            .flat_map(|_| indices.iter().copied())
+            .collect_vec();
+        let rtg_levels = rtg_levels
+            .iter()
+            .flat_map(|l| vec![*l; indices.len()])
+            .collect_vec();
+
+        EvaluationKey::new(params, sk, rlk_levels, &rtg_levels, &rtg_indices, rng)
+    }
+
     pub fn get_rtg_ref(&self, rot_by: isize, level: usize) -> &GaloisKey {
         self.rtgs.get(&(rot_by, level)).expect("Rtg missing!")
     }
+
+    pub fn try_get_rtg_ref(&self, rot_by: isize, level: usize) -> Result<&GaloisKey, BfvError> {
+        self.rtgs
+            .get(&(rot_by, level))
+            .ok_or(BfvError::MissingGaloisKey {
+                rotate_by: rot_by,
+                level,
+            })
+    }
+
+    pub fn try_get_rlk_ref(&self, level: usize) -> Result<&RelinearizationKey, BfvError> {
+        self.rlks
+            .get(&level)
+            .ok_or(BfvError::MissingRelinearizationKey { level })
+    }
 }
 
 #[cfg(test)]
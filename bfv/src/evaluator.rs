@@ -1,10 +1,15 @@
 use crate::relinearization_key::RelinearizationKey;
-use crate::{BfvParameters, Ciphertext, EvaluationKey, PolyType};
-use crate::{Encoding, GaloisKey, Plaintext, SecretKey};
+use crate::{
+    naf, sum_slots_rotations, Encoding, EncodingType, GaloisKey, NoiseEstimate, Plaintext,
+    PublicKey, SecretKey,
+};
+use crate::{BfvError, BfvParameters, Ciphertext, EvaluationKey, PolyType};
+use crate::{KeySwitchingKey, KeySwitchingMethod, LinearTransform, QpCiphertext};
 use crate::{Poly, Representation};
 use itertools::{izip, Itertools};
 use num_bigint::{BigUint, RandBigInt};
 use rand::{thread_rng, CryptoRng, Rng, RngCore};
+use std::collections::HashMap;
 
 pub struct Evaluator {
     pub(crate) params: BfvParameters,
@@ -27,13 +32,15 @@ impl Evaluator {
     }
 
     pub fn mul(&self, lhs: &Ciphertext, rhs: &Ciphertext) -> Ciphertext {
-        let mut res = self.mul_lazy(lhs, rhs);
-        self.scale_and_round(&mut res)
+        self.try_mul(lhs, rhs).unwrap()
+    }
+
+    pub fn try_mul(&self, lhs: &Ciphertext, rhs: &Ciphertext) -> Result<Ciphertext, BfvError> {
+        let mut res = self.try_mul_lazy(lhs, rhs)?;
+        self.try_scale_and_round(&mut res)
     }
 
     pub fn mul_lazy(&self, lhs: &Ciphertext, rhs: &Ciphertext) -> Ciphertext {
-        assert!(lhs.c.len() == 2);
-        assert!(rhs.c.len() == 2);
         #[cfg(debug_assertions)]
         {
             // We save 2 ntts if polynomial passed to `fast_expand_crt_basis_p_over_q` is in coefficient form. Hence
@@ -47,102 +54,113 @@ impl Evaluator {
                 panic!("Different representation in multiply1 only allows when self is in `Evalaution`")
             }
         }
-        assert!(lhs.level == rhs.level);
-        assert!(lhs.poly_type == rhs.poly_type);
-        assert!(lhs.poly_type == PolyType::Q);
+        self.try_mul_lazy(lhs, rhs).unwrap()
+    }
+
+    /// Returns tensor product of `lhs` and `rhs` in basis PQ without scaling down by t/Q.
+    ///
+    /// Ciphertexts can have any number of polynomials. If `lhs` has n polynomials and `rhs` has m polynomials,
+    /// the output has n + m - 1 polynomials.
+    ///
+    /// Returns error if either ciphertext is empty, ciphertexts are at different levels or have different
+    /// encoding types, or either ciphertext is not of `PolyType::Q`.
+    pub fn try_mul_lazy(&self, lhs: &Ciphertext, rhs: &Ciphertext) -> Result<Ciphertext, BfvError> {
+        check_ciphertext_not_empty(lhs)?;
+        check_ciphertext_not_empty(rhs)?;
+        check_level(lhs.level, rhs.level)?;
+        check_encoding_type(&lhs.encoding_type, &rhs.encoding_type)?;
+        check_poly_type(&PolyType::Q, &lhs.poly_type)?;
+        check_poly_type(&PolyType::Q, &rhs.poly_type)?;
 
         let level = lhs.level;
         let q_ctx = self.params.poly_ctx(&PolyType::Q, level);
         let p_ctx = self.params.poly_ctx(&PolyType::P, level);
         let pq_ctx = self.params.poly_ctx(&PolyType::PQ, level);
 
-        // let mut now = std::time::Instant::now();
-        let mut c00 = q_ctx.expand_crt_basis(
-            &lhs.c[0],
-            &pq_ctx,
-            &p_ctx,
-            &self.params.ql_hat_modpl[level],
-            &self.params.ql_hat_inv_modql[level],
-            &self.params.ql_hat_inv_modql_shoup[level],
-            &self.params.ql_inv[level],
-            &self.params.alphal_modpl[level],
-        );
-        let mut c01 = q_ctx.expand_crt_basis(
-            &lhs.c[1],
-            &pq_ctx,
-            &p_ctx,
-            &self.params.ql_hat_modpl[level],
-            &self.params.ql_hat_inv_modql[level],
-            &self.params.ql_hat_inv_modql_shoup[level],
-            &self.params.ql_inv[level],
-            &self.params.alphal_modpl[level],
-        );
-        // println!("Extend1 {:?}", now.elapsed());
-        if c00.representation != Representation::Evaluation {
-            pq_ctx.change_representation(&mut c00, Representation::Evaluation);
-            pq_ctx.change_representation(&mut c01, Representation::Evaluation);
-        }
-        // println!("Extend1 (In Evaluation) {:?}", now.elapsed());
-
-        // now = std::time::Instant::now();
-        let mut c10 = q_ctx.fast_expand_crt_basis_p_over_q(
-            &rhs.c[0],
-            &p_ctx,
-            &pq_ctx,
-            &self.params.neg_pql_hat_inv_modql[level],
-            &self.params.neg_pql_hat_inv_modql_shoup[level],
-            &self.params.ql_inv[level],
-            &self.params.ql_inv_modpl[level],
-            &self.params.pl_hat_modql[level],
-            &self.params.pl_hat_inv_modpl[level],
-            &self.params.pl_hat_inv_modpl_shoup[level],
-            &self.params.pl_inv[level],
-            &self.params.alphal_modql[level],
-        );
-        let mut c11 = q_ctx.fast_expand_crt_basis_p_over_q(
-            &rhs.c[1],
-            &p_ctx,
-            &pq_ctx,
-            &self.params.neg_pql_hat_inv_modql[level],
-            &self.params.neg_pql_hat_inv_modql_shoup[level],
-            &self.params.ql_inv[level],
-            &self.params.ql_inv_modpl[level],
-            &self.params.pl_hat_modql[level],
-            &self.params.pl_hat_inv_modpl[level],
-            &self.params.pl_hat_inv_modpl_shoup[level],
-            &self.params.pl_inv[level],
-            &self.params.alphal_modql[level],
-        );
-        // println!("Extend2 {:?}", now.elapsed());
-        pq_ctx.change_representation(&mut c10, Representation::Evaluation);
-        pq_ctx.change_representation(&mut c11, Representation::Evaluation);
-        // println!("Extend2 (In Evaluation) {:?}", now.elapsed());
+        let lhs_pq = lhs
+            .c
+            .iter()
+            .map(|p| {
+                let mut p = q_ctx.expand_crt_basis(
+                    p,
+                    &pq_ctx,
+                    &p_ctx,
+                    &self.params.ql_hat_modpl[level],
+                    &self.params.ql_hat_inv_modql[level],
+                    &self.params.ql_hat_inv_modql_shoup[level],
+                    &self.params.ql_inv[level],
+                    &self.params.alphal_modpl[level],
+                );
+                if p.representation != Representation::Evaluation {
+                    pq_ctx.change_representation(&mut p, Representation::Evaluation);
+                }
+                p
+            })
+            .collect_vec();
 
-        // now = std::time::Instant::now();
-        // tensor
-        // c00 * c10
-        let c_r0 = pq_ctx.mul(&c00, &c10);
+        let rhs_pq = rhs
+            .c
+            .iter()
+            .map(|p| {
+                let mut p = q_ctx.fast_expand_crt_basis_p_over_q(
+                    p,
+                    &p_ctx,
+                    &pq_ctx,
+                    &self.params.neg_pql_hat_inv_modql[level],
+                    &self.params.neg_pql_hat_inv_modql_shoup[level],
+                    &self.params.ql_inv[level],
+                    &self.params.ql_inv_modpl[level],
+                    &self.params.pl_hat_modql[level],
+                    &self.params.pl_hat_inv_modpl[level],
+                    &self.params.pl_hat_inv_modpl_shoup[level],
+                    &self.params.pl_inv[level],
+                    &self.params.alphal_modql[level],
+                );
+                pq_ctx.change_representation(&mut p, Representation::Evaluation);
+                p
+            })
+            .collect_vec();
 
-        // c00 * c11 + c01 * c10
-        pq_ctx.mul_assign(&mut c00, &c11);
-        pq_ctx.mul_assign(&mut c10, &c01);
-        pq_ctx.add_assign(&mut c00, &c10);
+        // tensor
+        // c_k = \sum_{i + j = k} lhs_i * rhs_j
+        let mut c: Vec<Option<Poly>> = vec![None; lhs_pq.len() + rhs_pq.len() - 1];
+        lhs_pq.iter().enumerate().for_each(|(i, l)| {
+            rhs_pq
+                .iter()
+                .enumerate()
+                .for_each(|(j, r)| match c[i + j].as_mut() {
+                    Some(ck) => {
+                        let lr = pq_ctx.mul(l, r);
+                        pq_ctx.add_assign(ck, &lr);
+                    }
+                    None => {
+                        c[i + j] = Some(pq_ctx.mul(l, r));
+                    }
+                });
+        });
 
-        // c01 * c11
-        pq_ctx.mul_assign(&mut c01, &c11);
-        // println!("Tensor {:?}", now.elapsed());
+        // noise estimate of the product after scaling down by t/Q
+        let noise = lhs.noise.zip(rhs.noise).map(|(l, r)| {
+            NoiseEstimate::mul(&l, lhs.c.len(), &r, rhs.c.len(), &self.params, level)
+        });
 
-        Ciphertext {
-            c: vec![c_r0, c00, c01],
+        Ok(Ciphertext {
+            c: c.into_iter().map(|p| p.unwrap()).collect(),
             poly_type: PolyType::PQ,
             level: level,
             seed: None,
-        }
+            encoding_type: lhs.encoding_type.clone(),
+            noise,
+        })
     }
 
     pub fn scale_and_round(&self, c0: &mut Ciphertext) -> Ciphertext {
+        self.try_scale_and_round(c0).unwrap()
+    }
+
+    pub fn try_scale_and_round(&self, c0: &mut Ciphertext) -> Result<Ciphertext, BfvError> {
         // debug_assert!(c0.c[0].representation == Representation::E)
-        assert!(c0.poly_type == PolyType::PQ);
+        check_poly_type(&PolyType::PQ, &c0.poly_type)?;
         let level = c0.level;
         let pq_ctx = self.params.poly_ctx(&PolyType::PQ, level);
         let q_ctx = self.params.poly_ctx(&PolyType::Q, level);
@@ -164,76 +182,653 @@ impl Evaluator {
                 })
                 .collect_vec();
 
-        Ciphertext {
+        Ok(Ciphertext {
             c,
             poly_type: PolyType::Q,
             level,
             seed: None,
-        }
+            encoding_type: c0.encoding_type.clone(),
+            // `try_mul_lazy` estimates noise after scaling
+            noise: c0.noise,
+        })
     }
 
     pub fn relinearize(&self, c0: &Ciphertext, ek: &EvaluationKey) -> Ciphertext {
-        ek.rlks
-            .get(&c0.level)
-            .expect("Rlk missing!")
-            .relinearize(&c0, &self.params)
+        self.try_relinearize(c0, ek).unwrap()
+    }
+
+    /// Relinearizes ciphertext with 3 or more polynomials using relinearization key in `ek` at ciphertext's level.
+    ///
+    /// Ciphertext with k + 1 polynomials requires relinearization key with max. degree of at least k
+    /// (see `EvaluationKey::new_with_rlk_max_degree`).
+    ///
+    /// Returns error if ciphertext has less than 3 polynomials or its polynomials are not in `Coefficient`
+    /// representation, or relinearization key at ciphertext's level is missing or its max. degree is too small.
+    pub fn try_relinearize(
+        &self,
+        c0: &Ciphertext,
+        ek: &EvaluationKey,
+    ) -> Result<Ciphertext, BfvError> {
+        Ok(self
+            .check_relinearize(c0, ek)?
+            .relinearize(c0, &self.params))
+    }
+
+    pub fn relinearize_qp(&self, c0: &Ciphertext, ek: &EvaluationKey) -> QpCiphertext {
+        self.try_relinearize_qp(c0, ek).unwrap()
+    }
+
+    /// Same as `try_relinearize` but returns relinearized ciphertext with key switched polynomials in QP (see
+    /// `QpCiphertext`)
+    pub fn try_relinearize_qp(
+        &self,
+        c0: &Ciphertext,
+        ek: &EvaluationKey,
+    ) -> Result<QpCiphertext, BfvError> {
+        Ok(self
+            .check_relinearize(c0, ek)?
+            .relinearize_qp(c0, &self.params))
+    }
+
+    /// Checks that ciphertext can be relinearized with `ek` and returns relinearization key at its level
+    fn check_relinearize<'a>(
+        &self,
+        c0: &Ciphertext,
+        ek: &'a EvaluationKey,
+    ) -> Result<&'a RelinearizationKey, BfvError> {
+        check_poly_type(&PolyType::Q, &c0.poly_type)?;
+        if c0.c.len() < 3 {
+            return Err(BfvError::CiphertextSizeMismatch {
+                expected: 3,
+                found: c0.c.len(),
+            });
+        }
+        check_ciphertext_representation(c0, Representation::Coefficient)?;
+
+        let rlk = ek.try_get_rlk_ref(c0.level)?;
+        if c0.c.len() - 1 > rlk.max_degree() {
+            return Err(BfvError::RelinearizationKeyDegreeTooSmall {
+                max_degree: rlk.max_degree(),
+                degree: c0.c.len() - 1,
+            });
+        }
+        Ok(rlk)
     }
 
     pub fn rotate(&self, c0: &Ciphertext, rotate_by: isize, ek: &EvaluationKey) -> Ciphertext {
-        ek.rtgs
-            .get(&(rotate_by, c0.level))
-            .expect(&format!("Rtg missing! :{rotate_by} {}", c0.level))
-            .rotate(&c0, &self.params)
+        self.try_rotate(c0, rotate_by, ek).unwrap()
+    }
+
+    /// Rotates ciphertext by `rotate_by` using galois key in `ek` at ciphertext's level
+    ///
+    /// If `ek` does not have galois key for `rotate_by`, rotation is decomposed into power-of-two rotations
+    /// using NAF (see `EvaluationKey::new_with_power_of_two_rotations`) at the cost of a key switch per
+    /// non-zero digit.
+    ///
+    /// Returns error if ciphertext does not have 2 polynomials or galois keys for `rotate_by` (or for its
+    /// decomposition) at ciphertext's level are missing.
+    pub fn try_rotate(
+        &self,
+        c0: &Ciphertext,
+        rotate_by: isize,
+        ek: &EvaluationKey,
+    ) -> Result<Ciphertext, BfvError> {
+        check_poly_type(&PolyType::Q, &c0.poly_type)?;
+        check_ciphertext_size(c0, 2)?;
+
+        let level = c0.level;
+        if let Ok(rtg) = ek.try_get_rtg_ref(rotate_by, level) {
+            return Ok(rtg.rotate(c0, &self.params));
+        }
+
+        // row swap cannot be decomposed
+        if rotate_by == (2 * self.params.degree - 1) as isize {
+            return Err(BfvError::MissingGaloisKey { rotate_by, level });
+        }
+
+        // Each row has N/2 slots. Hence reduce `rotate_by` to range (-N/4, N/4] to minimise no. of digits.
+        let row_size = (self.params.degree / 2) as isize;
+        let mut r = rotate_by.rem_euclid(row_size);
+        if r > row_size / 2 {
+            r -= row_size;
+        }
+        if r == 0 {
+            return Ok(c0.clone());
+        }
+
+        let rtgs = naf(r)
+            .into_iter()
+            .map(|d| {
+                // rotating by -N/4 is same as rotating by N/4
+                let d = if d == -row_size / 2 { row_size / 2 } else { d };
+                ek.try_get_rtg_ref(d, level)
+                    .map_err(|_| BfvError::MissingGaloisKey { rotate_by, level })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut ct = rtgs[0].rotate(c0, &self.params);
+        rtgs.iter().skip(1).for_each(|rtg| {
+            ct = rtg.rotate(&ct, &self.params);
+        });
+        Ok(ct)
+    }
+
+    pub fn sum_slots(&self, c0: &Ciphertext, width: usize, ek: &EvaluationKey) -> Ciphertext {
+        self.try_sum_slots(c0, width, ek).unwrap()
+    }
+
+    /// Sums slots in windows of `width` using log(width) rotations and additions. After summation slot i
+    /// holds sum of slots i, i+1, ..., i+width-1, where slot indices wrap around within a row if `width` <= N/2.
+    /// Thus slots at multiples of `width` hold sums of aligned windows. If `width` is N every slot holds sum
+    /// of all slots (see `inner_sum`).
+    ///
+    /// Required galois keys can be generated with `EvaluationKey::new_with_sum_slots_rotations`.
+    ///
+    /// Returns error if `width` is not a power of two or exceeds N, ciphertext does not have 2 polynomials,
+    /// or galois keys at ciphertext's level are missing.
+    pub fn try_sum_slots(
+        &self,
+        c0: &Ciphertext,
+        width: usize,
+        ek: &EvaluationKey,
+    ) -> Result<Ciphertext, BfvError> {
+        let degree = self.params.degree;
+        if !width.is_power_of_two() || width > degree {
+            return Err(BfvError::InvalidSumWidth { width, degree });
+        }
+        check_poly_type(&PolyType::Q, &c0.poly_type)?;
+        check_ciphertext_size(c0, 2)?;
+
+        let mut ct = c0.clone();
+        for rotate_by in sum_slots_rotations(degree, width) {
+            let rotated = self.try_rotate(&ct, rotate_by, ek)?;
+            self.try_add_assign(&mut ct, &rotated)?;
+        }
+        Ok(ct)
+    }
+
+    pub fn inner_sum(&self, c0: &Ciphertext, ek: &EvaluationKey) -> Ciphertext {
+        self.try_inner_sum(c0, ek).unwrap()
+    }
+
+    /// Sums all slots of ciphertext. Every slot of the output holds the sum.
+    ///
+    /// Returns error if ciphertext does not have 2 polynomials or galois keys for `sum_slots` with width N
+    /// at ciphertext's level are missing.
+    pub fn try_inner_sum(
+        &self,
+        c0: &Ciphertext,
+        ek: &EvaluationKey,
+    ) -> Result<Ciphertext, BfvError> {
+        self.try_sum_slots(c0, self.params.degree, ek)
+    }
+
+    pub fn rotate_many(
+        &self,
+        c0: &Ciphertext,
+        rotations: &[isize],
+        ek: &EvaluationKey,
+    ) -> Vec<Ciphertext> {
+        self.try_rotate_many(c0, rotations, ek).unwrap()
+    }
+
+    /// Rotates ciphertext by each of `rotations` using hoisting. c1 is decomposed (and, for hybrid key
+    /// switching, switched to QP) once, instead of once per rotation, and each rotation only applies the
+    /// substitution to the decomposed parts before inner product with galois key. Thus rotating by many
+    /// values is much cheaper than calling `rotate` for each.
+    ///
+    /// Rotations for which `ek` does not have galois key fall back to `rotate`, ie they are decomposed into
+    /// power-of-two rotations and are not hoisted. Rotation by 0 returns the ciphertext.
+    ///
+    /// Returns error if ciphertext does not have 2 polynomials or galois keys for any of `rotations` (or for
+    /// its decomposition) at ciphertext's level are missing.
+    pub fn try_rotate_many(
+        &self,
+        c0: &Ciphertext,
+        rotations: &[isize],
+        ek: &EvaluationKey,
+    ) -> Result<Vec<Ciphertext>, BfvError> {
+        check_poly_type(&PolyType::Q, &c0.poly_type)?;
+        check_ciphertext_size(c0, 2)?;
+
+        let level = c0.level;
+
+        // decompose c1 lazily, only if some rotation has galois key, once per key switching method
+        let mut c1_parts = HashMap::new();
+        rotations
+            .iter()
+            .map(|rotate_by| match ek.try_get_rtg_ref(*rotate_by, level) {
+                Ok(rtg) => {
+                    let method = rtg.ksk_key.method();
+                    let c1_parts = c1_parts
+                        .entry(method)
+                        .or_insert_with(|| self.decompose_c1(c0, method));
+                    Ok(rtg.rotate_decomposed(c0, c1_parts, &self.params))
+                }
+                Err(_) => self.try_rotate(c0, *rotate_by, ek),
+            })
+            .collect()
+    }
+
+    pub fn rotate_qp(&self, c0: &Ciphertext, rotate_by: isize, ek: &EvaluationKey) -> QpCiphertext {
+        self.try_rotate_qp(c0, rotate_by, ek).unwrap()
+    }
+
+    /// Same as `try_rotate` but returns rotated ciphertext with key switched polynomials in QP (see
+    /// `QpCiphertext`). Rotated ciphertexts can be accumulated with `add_assign_qp` and switched to Q
+    /// once with `mod_down_qp`.
+    ///
+    /// Unlike `try_rotate`, returns error if `ek` does not have galois key for `rotate_by` at ciphertext's
+    /// level, since rotation cannot be decomposed without mod down.
+    pub fn try_rotate_qp(
+        &self,
+        c0: &Ciphertext,
+        rotate_by: isize,
+        ek: &EvaluationKey,
+    ) -> Result<QpCiphertext, BfvError> {
+        Ok(self
+            .try_rotate_many_qp(c0, &[rotate_by], ek)?
+            .pop()
+            .unwrap())
+    }
+
+    pub fn rotate_many_qp(
+        &self,
+        c0: &Ciphertext,
+        rotations: &[isize],
+        ek: &EvaluationKey,
+    ) -> Vec<QpCiphertext> {
+        self.try_rotate_many_qp(c0, rotations, ek).unwrap()
+    }
+
+    /// Same as `try_rotate_many` but returns rotated ciphertexts with key switched polynomials in QP (see
+    /// `try_rotate_qp`).
+    ///
+    /// Returns error if ciphertext does not have 2 polynomials or `ek` does not have galois key for any of
+    /// `rotations` at ciphertext's level.
+    pub fn try_rotate_many_qp(
+        &self,
+        c0: &Ciphertext,
+        rotations: &[isize],
+        ek: &EvaluationKey,
+    ) -> Result<Vec<QpCiphertext>, BfvError> {
+        check_poly_type(&PolyType::Q, &c0.poly_type)?;
+        check_ciphertext_size(c0, 2)?;
+
+        let rtgs = rotations
+            .iter()
+            .map(|rotate_by| ek.try_get_rtg_ref(*rotate_by, c0.level))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut c1_parts = HashMap::new();
+        Ok(rtgs
+            .iter()
+            .map(|rtg| {
+                let method = rtg.ksk_key.method();
+                let c1_parts = c1_parts
+                    .entry(method)
+                    .or_insert_with(|| self.decompose_c1(c0, method));
+                rtg.rotate_decomposed_qp(c0, c1_parts, &self.params)
+            })
+            .collect_vec())
+    }
+
+    pub fn add_assign_qp(&self, c0: &mut QpCiphertext, c1: &QpCiphertext) {
+        self.try_add_assign_qp(c0, c1).unwrap()
+    }
+
+    /// Adds ciphertexts with key switched polynomials in QP, without switching them to Q
+    ///
+    /// Returns error if ciphertexts are at different levels, were key switched with different key switching
+    /// methods, or their polynomials in Q are in different representations.
+    pub fn try_add_assign_qp(
+        &self,
+        c0: &mut QpCiphertext,
+        c1: &QpCiphertext,
+    ) -> Result<(), BfvError> {
+        check_level(c0.level, c1.level)?;
+        if c0.method != c1.method {
+            return Err(BfvError::KeySwitchingMethodMismatch {
+                expected: c0.method,
+                found: c1.method,
+            });
+        }
+        izip!(c0.c.iter(), c1.c.iter()).try_for_each(|(p0, p1)| {
+            check_representation(&p0.representation, &p1.representation)
+        })?;
+
+        let q_ctx = self.params.poly_ctx(&PolyType::Q, c0.level);
+        let ctx = KeySwitchingKey::decomposition_ctx_with_method(&self.params, c0.method, c0.level);
+        izip!(c0.c.iter_mut(), c1.c.iter()).for_each(|(p0, p1)| q_ctx.add_assign(p0, p1));
+        izip!(c0.d.iter_mut(), c1.d.iter()).for_each(|(p0, p1)| ctx.add_assign(p0, p1));
+        c0.noise = c0.noise.zip(c1.noise).map(|(n0, n1)| n0.add(&n1));
+        Ok(())
+    }
+
+    /// Switches key switched polynomials of ciphertext from QP to Q, ie a single mod down per polynomial for
+    /// sum of any no. of key switched ciphertexts. Output is in representation of ciphertext's polynomials in Q.
+    pub fn mod_down_qp(&self, c0: QpCiphertext) -> Ciphertext {
+        c0.mod_down(&self.params)
+    }
+
+    /// Returns decomposition of c1 of ciphertext for key switching with keys of key switching `method` (see
+    /// `KeySwitchingKey::decompose`)
+    fn decompose_c1(&self, c0: &Ciphertext, method: KeySwitchingMethod) -> Vec<Poly> {
+        let q_ctx = self.params.poly_ctx(&PolyType::Q, c0.level);
+        let mut c1 = c0.c[1].clone();
+        q_ctx.change_representation(&mut c1, Representation::Coefficient);
+        KeySwitchingKey::decompose_with_method(&self.params, method, &c1, c0.level)
+    }
+
+    pub fn linear_transform(
+        &self,
+        c0: &Ciphertext,
+        lt: &LinearTransform,
+        ek: &EvaluationKey,
+    ) -> Ciphertext {
+        self.try_linear_transform(c0, lt, ek).unwrap()
+    }
+
+    /// Multiplies encrypted vector with plaintext matrix of `lt` using BSGS algorithm (see `LinearTransform`).
+    /// Baby-step rotations are hoisted (see `rotate_many`) and giant-step rotations are accumulated in QP
+    /// (see `rotate_qp`).
+    ///
+    /// Returns error if ciphertext does not have 2 polynomials, is not at level of `lt`, or galois keys for
+    /// `LinearTransform::rotations` at ciphertext's level are missing.
+    pub fn try_linear_transform(
+        &self,
+        c0: &Ciphertext,
+        lt: &LinearTransform,
+        ek: &EvaluationKey,
+    ) -> Result<Ciphertext, BfvError> {
+        check_poly_type(&PolyType::Q, &c0.poly_type)?;
+        check_ciphertext_size(c0, 2)?;
+        check_level(lt.level(), c0.level)?;
+
+        // hoisted baby steps
+        let baby_rotations = lt.baby_rotations();
+        let baby_steps = self.try_rotate_many(
+            c0,
+            &baby_rotations.iter().map(|j| *j as isize).collect_vec(),
+            ek,
+        )?;
+        let baby_steps: HashMap<_, _> = izip!(baby_rotations, baby_steps)
+            .map(|(j, mut ct)| {
+                self.ciphertext_change_representation(&mut ct, Representation::Evaluation);
+                (j, ct)
+            })
+            .collect();
+
+        // giant-step rotations are accumulated in QP and switched to Q once
+        let mut res: Option<Ciphertext> = None;
+        let mut res_qp: Option<QpCiphertext> = None;
+        for (giant, diagonals) in lt.giant_groups() {
+            let mut inner = self.try_mul_plaintext(&baby_steps[&diagonals[0].0], diagonals[0].1)?;
+            for (j, pt) in diagonals.iter().skip(1) {
+                self.try_add_assign(&mut inner, &self.try_mul_plaintext(&baby_steps[j], pt)?)?;
+            }
+
+            if giant == 0 {
+                res = Some(inner);
+                continue;
+            }
+
+            let inner = self.try_rotate_qp(&inner, giant as isize, ek)?;
+            match res_qp.as_mut() {
+                Some(res_qp) => self.try_add_assign_qp(res_qp, &inner)?,
+                None => res_qp = Some(inner),
+            }
+        }
+
+        let mut res = match (res, res_qp) {
+            (Some(mut res), Some(res_qp)) => {
+                self.try_add_assign(&mut res, &self.mod_down_qp(res_qp))?;
+                res
+            }
+            (None, Some(res_qp)) => self.mod_down_qp(res_qp),
+            (Some(res), None) => res,
+            // all diagonals are zero
+            (None, None) => self.mul_scalar(c0, 0),
+        };
+        self.ciphertext_change_representation(&mut res, c0.c[0].representation.clone());
+        Ok(res)
+    }
+
+    pub fn evaluate_polynomial(
+        &self,
+        c0: &Ciphertext,
+        coeffs: &[u64],
+        ek: &EvaluationKey,
+    ) -> Ciphertext {
+        self.try_evaluate_polynomial(c0, coeffs, ek).unwrap()
+    }
+
+    /// Evaluates polynomial p(x) = coeffs[0] + coeffs[1] x + ... + coeffs[d] x^d over Z_t on every slot of
+    /// ciphertext using Paterson-Stockmeyer algorithm.
+    ///
+    /// Writing L = ceil(log2(d + 1)), p is evaluated with k = 2^ceil(L/2) baby-step powers x, x^2, ..., x^{k-1}
+    /// and giant-step powers x^k, x^2k, ..., x^{2^{L-1}}. p is recursively split as p = q x^{2^j k} + r and
+    /// polynomials of degree < k are evaluated with scalar multiplications and additions only. Thus
+    /// multiplicative depth of evaluation is ceil(log2(d)), which is minimal for degree d, and each
+    /// ciphertext multiplication is relinearized with relinearization key in `ek`.
+    ///
+    /// Output is at ciphertext's level and in its representation, since multiplications do not switch
+    /// modulus. Coefficients are reduced mod t and trailing zero coefficients do not count towards degree.
+    ///
+    /// Returns error if ciphertext does not have 2 polynomials or relinearization key at ciphertext's level
+    /// is missing (it is not required if d < 2).
+    pub fn try_evaluate_polynomial(
+        &self,
+        c0: &Ciphertext,
+        coeffs: &[u64],
+        ek: &EvaluationKey,
+    ) -> Result<Ciphertext, BfvError> {
+        check_poly_type(&PolyType::Q, &c0.poly_type)?;
+        check_ciphertext_size(c0, 2)?;
+
+        let t = self.params.plaintext_modulus;
+        let coeffs = coeffs.iter().map(|c| c % t).collect_vec();
+        let degree = coeffs.iter().rposition(|c| *c != 0).unwrap_or(0);
+        let coeffs = if coeffs.is_empty() {
+            vec![0]
+        } else {
+            coeffs[..=degree].to_vec()
+        };
+
+        let log_degree = (usize::BITS - degree.leading_zeros()) as usize;
+        let baby_log = (log_degree + 1) / 2;
+        let k = 1 << baby_log;
+
+        let mut x = c0.clone();
+        self.ciphertext_change_representation(&mut x, Representation::Coefficient);
+
+        // x, x^2, ..., x^{min(k - 1, d)}. x^i = x^a x^{i - a}, where a is the largest power of two < i,
+        // has depth ceil(log2(i))
+        let mut baby_steps = vec![x];
+        for i in 2..std::cmp::min(k, degree + 1) {
+            let a = 1 << (usize::BITS - 1 - (i - 1).leading_zeros());
+            let x_i = self.mul_relinearize(&baby_steps[a - 1], &baby_steps[i - a - 1], ek)?;
+            baby_steps.push(x_i);
+        }
+
+        // x^k, x^2k, ..., x^{2^{L-1}}
+        let mut giant_steps: Vec<Ciphertext> = vec![];
+        for j in 0..(log_degree - baby_log) {
+            let x_j = if j == 0 {
+                let x_half = &baby_steps[k / 2 - 1];
+                self.mul_relinearize(x_half, x_half, ek)?
+            } else {
+                self.mul_relinearize(&giant_steps[j - 1], &giant_steps[j - 1], ek)?
+            };
+            giant_steps.push(x_j);
+        }
+
+        let mut res = match self.paterson_stockmeyer(&coeffs, k, &baby_steps, &giant_steps, ek)? {
+            PolyValue::Ciphertext(ct) => ct,
+            PolyValue::Constant(c) => {
+                let mut ct = self.mul_scalar(&baby_steps[0], 0);
+                self.try_add_scalar_assign(&mut ct, c)?;
+                ct
+            }
+        };
+        self.ciphertext_change_representation(&mut res, c0.c[0].representation.clone());
+        Ok(res)
+    }
+
+    /// Evaluates polynomial with `coeffs` of degree < k 2^{giant_steps.len()} as q x^{k 2^j} + r, where
+    /// j = giant_steps.len() - 1, recursively
+    fn paterson_stockmeyer(
+        &self,
+        coeffs: &[u64],
+        k: usize,
+        baby_steps: &[Ciphertext],
+        giant_steps: &[Ciphertext],
+        ek: &EvaluationKey,
+    ) -> Result<PolyValue, BfvError> {
+        let (x_j, giant_steps) = match giant_steps.split_last() {
+            Some(split) => split,
+            None => return self.evaluate_baby_polynomial(coeffs, baby_steps),
+        };
+
+        let half = k << giant_steps.len();
+        if coeffs.len() <= half {
+            return self.paterson_stockmeyer(coeffs, k, baby_steps, giant_steps, ek);
+        }
+        let q = self.paterson_stockmeyer(&coeffs[half..], k, baby_steps, giant_steps, ek)?;
+        let r = self.paterson_stockmeyer(&coeffs[..half], k, baby_steps, giant_steps, ek)?;
+
+        let mut res = match q {
+            PolyValue::Constant(0) => return Ok(r),
+            PolyValue::Constant(c) => self.mul_scalar(x_j, c),
+            PolyValue::Ciphertext(q) => self.mul_relinearize(&q, x_j, ek)?,
+        };
+        match r {
+            PolyValue::Constant(c) => {
+                if c != 0 {
+                    self.try_add_scalar_assign(&mut res, c)?;
+                }
+            }
+            PolyValue::Ciphertext(r) => self.try_add_assign(&mut res, &r)?,
+        }
+        Ok(PolyValue::Ciphertext(res))
+    }
+
+    /// Evaluates polynomial with `coeffs` of degree <= `baby_steps.len()` using scalar multiplications only
+    fn evaluate_baby_polynomial(
+        &self,
+        coeffs: &[u64],
+        baby_steps: &[Ciphertext],
+    ) -> Result<PolyValue, BfvError> {
+        let mut res: Option<Ciphertext> = None;
+        for (c, x_i) in izip!(coeffs.iter().skip(1), baby_steps.iter()) {
+            if *c == 0 {
+                continue;
+            }
+            let mut term = x_i.clone();
+            if *c != 1 {
+                self.mul_scalar_assign(&mut term, *c);
+            }
+            match res.as_mut() {
+                Some(res) => self.try_add_assign(res, &term)?,
+                None => res = Some(term),
+            }
+        }
+
+        match res {
+            Some(mut res) => {
+                if coeffs[0] != 0 {
+                    self.try_add_scalar_assign(&mut res, coeffs[0])?;
+                }
+                Ok(PolyValue::Ciphertext(res))
+            }
+            None => Ok(PolyValue::Constant(coeffs[0])),
+        }
+    }
+
+    /// Multiplies and relinearizes ciphertexts
+    fn mul_relinearize(
+        &self,
+        c0: &Ciphertext,
+        c1: &Ciphertext,
+        ek: &EvaluationKey,
+    ) -> Result<Ciphertext, BfvError> {
+        self.try_relinearize(&self.try_mul(c0, c1)?, ek)
     }
 
     pub fn add_assign(&self, c0: &mut Ciphertext, c1: &Ciphertext) {
-        // TODO: perform checks
+        self.try_add_assign(c0, c1).unwrap()
+    }
+
+    pub fn try_add_assign(&self, c0: &mut Ciphertext, c1: &Ciphertext) -> Result<(), BfvError> {
+        check_ciphertexts_match(c0, c1)?;
         let ctx = self.params.poly_ctx(&c0.poly_type, c0.level);
 
         izip!(c0.c.iter_mut(), c1.c.iter()).for_each(|(p0, p1)| {
             ctx.add_assign(p0, p1);
         });
         c0.seed = None;
+        c0.noise = sum_noise(c0, c1);
+        Ok(())
     }
 
     pub fn add(&self, c0: &Ciphertext, c1: &Ciphertext) -> Ciphertext {
+        self.try_add(c0, c1).unwrap()
+    }
+
+    pub fn try_add(&self, c0: &Ciphertext, c1: &Ciphertext) -> Result<Ciphertext, BfvError> {
+        check_ciphertexts_match(c0, c1)?;
         let ctx = self.params.poly_ctx(&c0.poly_type, c0.level);
 
         let c = izip!(c0.c.iter(), c1.c.iter())
             .map(|(p0, p1)| ctx.add(p0, p1))
             .collect_vec();
 
-        Ciphertext {
+        Ok(Ciphertext {
             c,
             poly_type: c0.poly_type.clone(),
             level: c0.level,
             seed: None,
-        }
+            encoding_type: c0.encoding_type.clone(),
+            noise: sum_noise(c0, c1),
+        })
     }
 
     pub fn sub_assign(&self, c0: &mut Ciphertext, c1: &Ciphertext) {
-        // TODO: perform checks
+        self.try_sub_assign(c0, c1).unwrap()
+    }
+
+    pub fn try_sub_assign(&self, c0: &mut Ciphertext, c1: &Ciphertext) -> Result<(), BfvError> {
+        check_ciphertexts_match(c0, c1)?;
         let ctx = self.params.poly_ctx(&c0.poly_type, c0.level);
 
         izip!(c0.c.iter_mut(), c1.c.iter()).for_each(|(p0, p1)| {
             ctx.sub_assign(p0, p1);
         });
         c0.seed = None;
+        c0.noise = sum_noise(c0, c1);
+        Ok(())
     }
 
     pub fn sub(&self, c0: &Ciphertext, c1: &Ciphertext) -> Ciphertext {
+        self.try_sub(c0, c1).unwrap()
+    }
+
+    pub fn try_sub(&self, c0: &Ciphertext, c1: &Ciphertext) -> Result<Ciphertext, BfvError> {
+        check_ciphertexts_match(c0, c1)?;
         let ctx = self.params.poly_ctx(&c0.poly_type, c0.level);
 
         let c = izip!(c0.c.iter(), c1.c.iter())
             .map(|(p0, p1)| ctx.sub(p0, p1))
             .collect_vec();
 
-        Ciphertext {
+        Ok(Ciphertext {
             c,
             poly_type: c0.poly_type.clone(),
             level: c0.level,
             seed: None,
-        }
+            encoding_type: c0.encoding_type.clone(),
+            noise: sum_noise(c0, c1),
+        })
     }
 
     pub fn negate_assign(&self, c0: &mut Ciphertext) {
@@ -251,6 +846,8 @@ impl Evaluator {
             poly_type: c0.poly_type.clone(),
             level: c0.level,
             seed: None,
+            encoding_type: c0.encoding_type.clone(),
+            noise: c0.noise,
         }
     }
 
@@ -262,6 +859,8 @@ impl Evaluator {
         });
 
         c0.seed = None;
+        // norm of `poly` is unknown
+        c0.noise = None;
     }
 
     pub fn mul_poly_assign(&self, c0: &mut Ciphertext, poly: &Poly) {
@@ -269,6 +868,8 @@ impl Evaluator {
         c0.c.iter_mut().for_each(|p0| ctx.mul_assign(p0, poly));
 
         c0.seed = None;
+        // norm of `poly` is unknown
+        c0.noise = None;
     }
 
     pub fn mul_poly(&self, c0: &Ciphertext, poly: &Poly) -> Ciphertext {
@@ -279,75 +880,276 @@ impl Evaluator {
             poly_type: c0.poly_type.clone(),
             level: c0.level,
             seed: None,
+            encoding_type: c0.encoding_type.clone(),
+            // norm of `poly` is unknown
+            noise: None,
         }
     }
 
     pub fn mul_plaintext_assign(&self, ct: &mut Ciphertext, pt: &Plaintext) {
-        assert!(ct.level() == pt.level());
-        assert!(ct.poly_type() == pt.mul_poly_type());
+        self.try_mul_plaintext_assign(ct, pt).unwrap()
+    }
 
-        self.mul_poly_assign(ct, pt.mul_poly_ref());
+    pub fn try_mul_plaintext_assign(
+        &self,
+        ct: &mut Ciphertext,
+        pt: &Plaintext,
+    ) -> Result<(), BfvError> {
+        self.check_mul_plaintext(ct, pt)?;
+        let noise = ct.noise.map(|n| n.mul_plaintext(&self.params));
+        self.mul_poly_assign(ct, pt.try_mul_poly_ref()?);
+        ct.noise = noise;
+        Ok(())
     }
 
     pub fn mul_plaintext(&self, ct: &Ciphertext, pt: &Plaintext) -> Ciphertext {
-        assert!(ct.level() == pt.level());
-        assert!(ct.poly_type() == pt.mul_poly_type());
+        self.try_mul_plaintext(ct, pt).unwrap()
+    }
 
-        self.mul_poly(ct, pt.mul_poly_ref())
+    pub fn try_mul_plaintext(
+        &self,
+        ct: &Ciphertext,
+        pt: &Plaintext,
+    ) -> Result<Ciphertext, BfvError> {
+        self.check_mul_plaintext(ct, pt)?;
+        let mut res = self.mul_poly(ct, pt.try_mul_poly_ref()?);
+        res.noise = ct.noise.map(|n| n.mul_plaintext(&self.params));
+        Ok(res)
+    }
+
+    /// Ciphertext must be in `Evaluation` representation with same level and `PolyType` as
+    /// plaintext's mul poly, and must have same encoding type as plaintext
+    fn check_mul_plaintext(&self, ct: &Ciphertext, pt: &Plaintext) -> Result<(), BfvError> {
+        check_level(pt.try_level()?, ct.level)?;
+        check_encoding_type(&ct.encoding_type, &pt.try_encoding()?.encoding_type)?;
+        check_poly_type(&pt.try_mul_poly_type()?, &ct.poly_type)?;
+        check_ciphertext_representation(ct, Representation::Evaluation)
     }
 
     pub fn add_assign_plaintext(&self, ct: &mut Ciphertext, pt: &Plaintext) {
-        assert!(ct.level() == pt.encoding.as_ref().unwrap().level);
-        assert!(ct.poly_type() == PolyType::Q);
+        self.try_add_assign_plaintext(ct, pt).unwrap()
+    }
+
+    pub fn try_add_assign_plaintext(
+        &self,
+        ct: &mut Ciphertext,
+        pt: &Plaintext,
+    ) -> Result<(), BfvError> {
+        self.check_add_sub_plaintext(ct, pt)?;
 
         let ctx = self.params.poly_ctx(&ct.poly_type, ct.level);
-        ctx.add_assign(&mut ct.c_ref_mut()[0], pt.add_sub_poly_ref());
+        // c1 does not change, hence seed remains valid
+        ctx.add_assign(&mut ct.c[0], pt.try_add_sub_poly_ref()?);
+        ct.noise = ct.noise.map(|n| n.add_plaintext());
+        Ok(())
     }
 
     pub fn add_plaintext(&self, ct: &Ciphertext, pt: &Plaintext) -> Ciphertext {
-        assert!(ct.level() == pt.encoding.as_ref().unwrap().level);
-        assert!(ct.poly_type() == PolyType::Q);
-        assert!(ct.c.len() == 2);
+        self.try_add_plaintext(ct, pt).unwrap()
+    }
+
+    pub fn try_add_plaintext(
+        &self,
+        ct: &Ciphertext,
+        pt: &Plaintext,
+    ) -> Result<Ciphertext, BfvError> {
+        check_ciphertext_size(ct, 2)?;
+        self.check_add_sub_plaintext(ct, pt)?;
 
         let ctx = self.params.poly_ctx(&ct.poly_type, ct.level);
-        let c0 = ctx.add(&ct.c_ref()[0], pt.add_sub_poly_ref());
+        let c0 = ctx.add(&ct.c_ref()[0], pt.try_add_sub_poly_ref()?);
 
         let c = vec![c0, ct.c_ref()[1].clone()];
 
-        Ciphertext {
+        Ok(Ciphertext {
             c,
             // since c1 does not changes seed remains valid
             seed: ct.seed.clone(),
             poly_type: ct.poly_type.clone(),
             level: ct.level,
-        }
+            encoding_type: ct.encoding_type.clone(),
+            noise: ct.noise.map(|n| n.add_plaintext()),
+        })
     }
 
     pub fn sub_assign_plaintext(&self, ct: &mut Ciphertext, pt: &Plaintext) {
-        assert!(ct.level() == pt.encoding.as_ref().unwrap().level);
-        assert!(ct.poly_type() == PolyType::Q);
+        self.try_sub_assign_plaintext(ct, pt).unwrap()
+    }
+
+    pub fn try_sub_assign_plaintext(
+        &self,
+        ct: &mut Ciphertext,
+        pt: &Plaintext,
+    ) -> Result<(), BfvError> {
+        self.check_add_sub_plaintext(ct, pt)?;
 
         let ctx = self.params.poly_ctx(&ct.poly_type, ct.level);
-        ctx.sub_assign(&mut ct.c_ref_mut()[0], pt.add_sub_poly_ref());
+        // c1 does not change, hence seed remains valid
+        ctx.sub_assign(&mut ct.c[0], pt.try_add_sub_poly_ref()?);
+        ct.noise = ct.noise.map(|n| n.add_plaintext());
+        Ok(())
     }
 
     pub fn sub_plaintext(&self, ct: &Ciphertext, pt: &Plaintext) -> Ciphertext {
-        assert!(ct.level() == pt.encoding.as_ref().unwrap().level);
-        assert!(ct.poly_type() == PolyType::Q);
-        assert!(ct.c.len() == 2);
+        self.try_sub_plaintext(ct, pt).unwrap()
+    }
+
+    pub fn try_sub_plaintext(
+        &self,
+        ct: &Ciphertext,
+        pt: &Plaintext,
+    ) -> Result<Ciphertext, BfvError> {
+        check_ciphertext_size(ct, 2)?;
+        self.check_add_sub_plaintext(ct, pt)?;
 
         let ctx = self.params.poly_ctx(&ct.poly_type, ct.level);
-        let c0 = ctx.sub(&ct.c_ref()[0], pt.add_sub_poly_ref());
+        let c0 = ctx.sub(&ct.c_ref()[0], pt.try_add_sub_poly_ref()?);
 
         let c = vec![c0, ct.c_ref()[1].clone()];
 
-        Ciphertext {
+        Ok(Ciphertext {
             c,
             // since c1 does not changes seed remains valid
             seed: ct.seed.clone(),
             poly_type: ct.poly_type.clone(),
             level: ct.level,
+            encoding_type: ct.encoding_type.clone(),
+            noise: ct.noise.map(|n| n.add_plaintext()),
+        })
+    }
+
+    /// Ciphertext must be of `PolyType::Q` with same level and encoding type as plaintext and its first
+    /// polynomial must be in same representation as plaintext's add_sub poly
+    fn check_add_sub_plaintext(&self, ct: &Ciphertext, pt: &Plaintext) -> Result<(), BfvError> {
+        check_level(pt.try_level()?, ct.level)?;
+        check_encoding_type(&ct.encoding_type, &pt.try_encoding()?.encoding_type)?;
+        check_poly_type(&PolyType::Q, &ct.poly_type)?;
+        if ct.c.is_empty() {
+            return Err(BfvError::CiphertextSizeMismatch {
+                expected: 2,
+                found: 0,
+            });
         }
+        check_representation(
+            &pt.try_add_sub_poly_ref()?.representation,
+            &ct.c[0].representation,
+        )
+    }
+
+    /// Multiplies all slots of ciphertext by `scalar`.
+    ///
+    /// Works for ciphertext of any `PolyType`, level and representation.
+    pub fn mul_scalar_assign(&self, ct: &mut Ciphertext, scalar: u64) {
+        let ctx = self.params.poly_ctx(&ct.poly_type, ct.level);
+
+        // Use centered representative of scalar in [-t/2, t/2) to keep noise growth small
+        let t = self.params.plaintext_modulus;
+        let scalar = scalar % t;
+        let scalars = ctx
+            .iter_moduli_ops()
+            .map(|modqi| {
+                if scalar >= t - scalar {
+                    modqi.neg_mod_fast((t - scalar) % modqi.modulus())
+                } else {
+                    scalar % modqi.modulus()
+                }
+            })
+            .collect_vec();
+
+        ct.c.iter_mut()
+            .for_each(|p| ctx.scalar_mul_assign(p, &scalars));
+        ct.seed = None;
+        ct.noise = ct
+            .noise
+            .map(|n| n.mul_scalar(std::cmp::min(scalar, t - scalar)));
+    }
+
+    pub fn mul_scalar(&self, ct: &Ciphertext, scalar: u64) -> Ciphertext {
+        let mut ct = ct.clone();
+        self.mul_scalar_assign(&mut ct, scalar);
+        ct
+    }
+
+    pub fn add_scalar_assign(&self, ct: &mut Ciphertext, scalar: u64) {
+        self.try_add_scalar_assign(ct, scalar).unwrap()
+    }
+
+    /// Adds `scalar` to ciphertext without encoding it as plaintext. For `EncodingType::Simd` this
+    /// adds `scalar` to all slots. For `EncodingType::Poly` only the constant coefficient changes.
+    ///
+    /// Returns error if ciphertext is not of `PolyType::Q`.
+    pub fn try_add_scalar_assign(&self, ct: &mut Ciphertext, scalar: u64) -> Result<(), BfvError> {
+        check_poly_type(&PolyType::Q, &ct.poly_type)?;
+        check_ciphertext_not_empty(ct)?;
+
+        let ctx = self.params.poly_ctx(&ct.poly_type, ct.level);
+        let scalars = self.scale_scalar(scalar, ct.level);
+        ctx.add_scalar_assign(&mut ct.c[0], &scalars);
+        ct.noise = ct.noise.map(|n| n.add_plaintext());
+        Ok(())
+    }
+
+    pub fn add_scalar(&self, ct: &Ciphertext, scalar: u64) -> Ciphertext {
+        self.try_add_scalar(ct, scalar).unwrap()
+    }
+
+    pub fn try_add_scalar(&self, ct: &Ciphertext, scalar: u64) -> Result<Ciphertext, BfvError> {
+        let mut ct = ct.clone();
+        self.try_add_scalar_assign(&mut ct, scalar)?;
+        Ok(ct)
+    }
+
+    pub fn sub_scalar_assign(&self, ct: &mut Ciphertext, scalar: u64) {
+        self.try_sub_scalar_assign(ct, scalar).unwrap()
+    }
+
+    /// Subtracts `scalar` from ciphertext without encoding it as plaintext. For `EncodingType::Simd` this
+    /// subtracts `scalar` from all slots. For `EncodingType::Poly` only the constant coefficient changes.
+    ///
+    /// Returns error if ciphertext is not of `PolyType::Q`.
+    pub fn try_sub_scalar_assign(&self, ct: &mut Ciphertext, scalar: u64) -> Result<(), BfvError> {
+        check_poly_type(&PolyType::Q, &ct.poly_type)?;
+        check_ciphertext_not_empty(ct)?;
+
+        let ctx = self.params.poly_ctx(&ct.poly_type, ct.level);
+        let scalars = izip!(
+            self.scale_scalar(scalar, ct.level).iter(),
+            ctx.iter_moduli_ops()
+        )
+        .map(|(v, modqi)| modqi.neg_mod_fast(*v))
+        .collect_vec();
+        ctx.add_scalar_assign(&mut ct.c[0], &scalars);
+        ct.noise = ct.noise.map(|n| n.add_plaintext());
+        Ok(())
+    }
+
+    pub fn sub_scalar(&self, ct: &Ciphertext, scalar: u64) -> Ciphertext {
+        self.try_sub_scalar(ct, scalar).unwrap()
+    }
+
+    pub fn try_sub_scalar(&self, ct: &Ciphertext, scalar: u64) -> Result<Ciphertext, BfvError> {
+        let mut ct = ct.clone();
+        self.try_sub_scalar_assign(&mut ct, scalar)?;
+        Ok(ct)
+    }
+
+    /// Returns `scalar` scaled by Q/t at `level` in RNS
+    ///
+    /// Same as `Plaintext::scale_m` for a constant polynomial but without NTTs.
+    fn scale_scalar(&self, scalar: u64, level: usize) -> Vec<u64> {
+        let modt = &self.params.plaintext_modulus_op;
+        let m = modt.mul_mod_fast(scalar % modt.modulus(), self.params.ql_modt[level]);
+
+        let ctx = self.params.poly_ctx(&PolyType::Q, level);
+        izip!(
+            ctx.iter_moduli_ops(),
+            self.params.neg_t_inv_modql[level].coefficients.outer_iter()
+        )
+        .map(|(modqi, neg_t_inv)| {
+            // `neg_t_inv_modql` is constant polynomial, hence all its values in `Evaluation` representation are equal.
+            modqi.mul_mod_fast(m % modqi.modulus(), neg_t_inv[0])
+        })
+        .collect_vec()
     }
 
     /// c0 = poly - c0
@@ -357,18 +1159,38 @@ impl Evaluator {
         ctx.neg_assign(&mut c0.c[1]);
 
         c0.seed = None;
+        // norm of `poly` is unknown
+        c0.noise = None;
     }
 
     pub fn mod_down_next(&self, c0: &mut Ciphertext) {
-        assert!(c0.poly_type == PolyType::Q);
+        self.try_mod_down_next(c0).unwrap()
+    }
+
+    /// Switches ciphertext to next level by dropping last modulus
+    ///
+    /// Returns error if ciphertext is not of `PolyType::Q` or is already at max level.
+    pub fn try_mod_down_next(&self, c0: &mut Ciphertext) -> Result<(), BfvError> {
+        check_poly_type(&PolyType::Q, &c0.poly_type)?;
         let level = c0.level;
+        if level >= self.params.max_level {
+            return Err(BfvError::InvalidLevel {
+                level: level + 1,
+                max_level: self.params.max_level,
+            });
+        }
+
         let ctx = self.params.poly_ctx(&c0.poly_type, level);
         c0.c.iter_mut().for_each(|p| {
             ctx.mod_down_next(p, &self.params.lastq_inv_modql[level]);
         });
         c0.level = level + 1;
+        c0.noise = c0
+            .noise
+            .map(|n| n.mod_down_next(c0.c.len(), &self.params, level));
 
         c0.seed = None;
+        Ok(())
     }
 
     pub fn mod_down_level(&self, c0: &mut Ciphertext, level: usize) {
@@ -382,6 +1204,14 @@ impl Evaluator {
         Plaintext::encode(m, &self.params, encoding)
     }
 
+    pub fn try_plaintext_encode(
+        &self,
+        m: &[u64],
+        encoding: Encoding,
+    ) -> Result<Plaintext, BfvError> {
+        Plaintext::try_encode(m, &self.params, encoding)
+    }
+
     pub fn encrypt<R: RngCore + CryptoRng>(
         &self,
         sk: &SecretKey,
@@ -391,6 +1221,15 @@ impl Evaluator {
         sk.encrypt(&self.params, pt, rng)
     }
 
+    pub fn encrypt_public<R: RngCore + CryptoRng>(
+        &self,
+        pk: &PublicKey,
+        pt: &Plaintext,
+        rng: &mut R,
+    ) -> Ciphertext {
+        pk.encrypt(&self.params, pt, rng)
+    }
+
     pub fn decrypt(&self, sk: &SecretKey, ct: &Ciphertext) -> Plaintext {
         sk.decrypt(ct, &self.params)
     }
@@ -399,10 +1238,42 @@ impl Evaluator {
         pt.decode(encoding, &self.params)
     }
 
+    pub fn try_plaintext_decode(
+        &self,
+        pt: &Plaintext,
+        encoding: Encoding,
+    ) -> Result<Vec<u64>, BfvError> {
+        pt.try_decode(encoding, &self.params)
+    }
+
     pub fn measure_noise(&self, sk: &SecretKey, ct: &Ciphertext) -> u64 {
         sk.measure_noise(ct, &self.params)
     }
 
+    /// Returns invariant noise budget of ciphertext in bits. Decryption is expected to fail once the budget
+    /// is 0.
+    ///
+    /// Unlike `measure_noise`, budget accounts for ciphertext modulus and plaintext modulus at the level of
+    /// ciphertext. Ciphertexts of `PolyType::PQ` (ie output of `mul_lazy`) are scaled and rounded first.
+    pub fn noise_budget(&self, sk: &SecretKey, ct: &Ciphertext) -> u64 {
+        if ct.poly_type == PolyType::PQ {
+            let ct = self.scale_and_round(&mut ct.clone());
+            return sk.noise_budget(&ct, &self.params);
+        }
+        sk.noise_budget(ct, &self.params)
+    }
+
+    /// Returns estimated noise budget of ciphertext in bits without the secret key (see `NoiseEstimate`).
+    /// Budget decreases with every operation and decryption is expected to fail once it is <= 0.
+    ///
+    /// Returns None if ciphertext does not have noise estimate or is not of `PolyType::Q`.
+    pub fn estimated_noise_budget(&self, ct: &Ciphertext) -> Option<f64> {
+        if ct.poly_type != PolyType::Q {
+            return None;
+        }
+        ct.noise.map(|n| n.budget(&self.params, ct.level))
+    }
+
     pub unsafe fn add_noise(&self, c0: &mut Ciphertext, bit_size: usize) {
         let ctx = self.params.poly_ctx(&c0.poly_type, c0.level);
 
@@ -420,18 +1291,161 @@ impl Evaluator {
             ctx.add_assign(p, &noise_poly);
         });
         c0.seed = None;
+        c0.noise = c0.noise.map(|n| n.add_bits(bit_size));
     }
 }
 
+/// Value of (sub-)polynomial evaluated by `Evaluator::try_evaluate_polynomial`. Polynomials of degree 0 are
+/// kept as constants to avoid multiplying ciphertexts by constants.
+enum PolyValue {
+    Constant(u64),
+    Ciphertext(Ciphertext),
+}
+
+/// Returns noise estimate of sum (or difference) of ciphertexts
+fn sum_noise(c0: &Ciphertext, c1: &Ciphertext) -> Option<NoiseEstimate> {
+    c0.noise.zip(c1.noise).map(|(n0, n1)| n0.add(&n1))
+}
+
+fn check_level(expected: usize, found: usize) -> Result<(), BfvError> {
+    if expected != found {
+        return Err(BfvError::LevelMismatch { expected, found });
+    }
+    Ok(())
+}
+
+fn check_poly_type(expected: &PolyType, found: &PolyType) -> Result<(), BfvError> {
+    if expected != found {
+        return Err(BfvError::PolyTypeMismatch {
+            expected: expected.clone(),
+            found: found.clone(),
+        });
+    }
+    Ok(())
+}
+
+fn check_representation(expected: &Representation, found: &Representation) -> Result<(), BfvError> {
+    if expected != found {
+        return Err(BfvError::RepresentationMismatch {
+            expected: expected.clone(),
+            found: found.clone(),
+        });
+    }
+    Ok(())
+}
+
+fn check_encoding_type(expected: &EncodingType, found: &EncodingType) -> Result<(), BfvError> {
+    if expected != found {
+        return Err(BfvError::EncodingMismatch {
+            expected: expected.clone(),
+            found: found.clone(),
+        });
+    }
+    Ok(())
+}
+
+fn check_ciphertext_size(ct: &Ciphertext, expected: usize) -> Result<(), BfvError> {
+    if ct.c.len() != expected {
+        return Err(BfvError::CiphertextSizeMismatch {
+            expected,
+            found: ct.c.len(),
+        });
+    }
+    Ok(())
+}
+
+fn check_ciphertext_not_empty(ct: &Ciphertext) -> Result<(), BfvError> {
+    if ct.c.is_empty() {
+        return Err(BfvError::CiphertextSizeMismatch {
+            expected: 1,
+            found: 0,
+        });
+    }
+    Ok(())
+}
+
+fn check_ciphertext_representation(
+    ct: &Ciphertext,
+    expected: Representation,
+) -> Result<(), BfvError> {
+    ct.c.iter()
+        .try_for_each(|p| check_representation(&expected, &p.representation))
+}
+
+/// Checks that ciphertexts can be added or subtracted
+fn check_ciphertexts_match(c0: &Ciphertext, c1: &Ciphertext) -> Result<(), BfvError> {
+    check_level(c0.level, c1.level)?;
+    check_poly_type(&c0.poly_type, &c1.poly_type)?;
+    check_encoding_type(&c0.encoding_type, &c1.encoding_type)?;
+    check_ciphertext_size(c1, c0.c.len())?;
+    izip!(c0.c.iter(), c1.c.iter())
+        .try_for_each(|(p0, p1)| check_representation(&p0.representation, &p1.representation))
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
 
-    use rand::thread_rng;
+    use rand::thread_rng;
+
+    use crate::{
+        relinearization_key::RelinearizationKey, utils::rot_to_galois_element, EncodingType,
+        KeySwitchingMethod, PolyCache,
+    };
+
+    use super::*;
+
+    #[test]
+    fn encoding_mismatch() {
+        let mut rng = thread_rng();
+        let params = BfvParameters::default(3, 1 << 4);
+        let m = params
+            .plaintext_modulus_op
+            .random_vec(params.degree, &mut rng);
+        let sk = SecretKey::random_with_params(&params, &mut rng);
+        let evaluator = Evaluator::new(params);
+
+        let simd_pt = evaluator.plaintext_encode(
+            &m,
+            Encoding::simd(0, PolyCache::All(PolyType::Q, Representation::Coefficient)),
+        );
+        let poly_pt = evaluator.plaintext_encode(
+            &m,
+            Encoding::poly(0, PolyCache::All(PolyType::Q, Representation::Coefficient)),
+        );
+        let simd_ct = evaluator.encrypt(&sk, &simd_pt, &mut rng);
+        let poly_ct = evaluator.encrypt(&sk, &poly_pt, &mut rng);
+        let mismatch = Err(BfvError::EncodingMismatch {
+            expected: EncodingType::Simd,
+            found: EncodingType::Poly,
+        });
 
-    use crate::{relinearization_key::RelinearizationKey, utils::rot_to_galois_element, PolyCache};
+        assert_eq!(evaluator.try_add(&simd_ct, &poly_ct).map(|_| ()), mismatch);
+        assert_eq!(evaluator.try_sub(&simd_ct, &poly_ct).map(|_| ()), mismatch);
+        assert_eq!(
+            evaluator.try_mul_lazy(&simd_ct, &poly_ct).map(|_| ()),
+            mismatch
+        );
+        assert_eq!(
+            evaluator.try_add_plaintext(&simd_ct, &poly_pt).map(|_| ()),
+            mismatch
+        );
+        assert_eq!(
+            evaluator.try_sub_plaintext(&simd_ct, &poly_pt).map(|_| ()),
+            mismatch
+        );
+        let mut ct = simd_ct.clone();
+        evaluator.ciphertext_change_representation(&mut ct, Representation::Evaluation);
+        assert_eq!(
+            evaluator.try_mul_plaintext(&ct, &poly_pt).map(|_| ()),
+            mismatch
+        );
 
-    use super::*;
+        // matching encodings are accepted
+        assert!(evaluator.try_add(&poly_ct, &poly_ct).is_ok());
+        assert!(evaluator.try_add_plaintext(&poly_ct, &poly_pt).is_ok());
+        assert!(evaluator.try_mul_plaintext(&ct, &simd_pt).is_ok());
+    }
 
     #[test]
     fn test_encryption_decryption() {
@@ -448,6 +1462,7 @@ mod tests {
         let ct = evaluator.encrypt(&sk, &pt, &mut rng);
 
         println!("Noise: {}", evaluator.measure_noise(&sk, &ct));
+        assert!(evaluator.noise_budget(&sk, &ct) > 0);
 
         let rm = evaluator.plaintext_decode(&evaluator.decrypt(&sk, &ct), Encoding::default());
         assert_eq!(rm, m);
@@ -477,6 +1492,7 @@ mod tests {
 
         let ct01 = evaluator.mul(&ct0, &ct1);
         println!("Noise: {}", evaluator.measure_noise(&sk, &ct01,));
+        assert!(evaluator.noise_budget(&sk, &ct01) > 0);
 
         // m0 = m0 * m1
         evaluator
@@ -495,6 +1511,7 @@ mod tests {
             "Noise after Relinearizartion: {}",
             evaluator.measure_noise(&sk, &ct01_relin,)
         );
+        assert!(evaluator.noise_budget(&sk, &ct01_relin) > 0);
         let res_m_relin =
             evaluator.plaintext_decode(&evaluator.decrypt(&sk, &ct01_relin), Encoding::default());
 
@@ -502,6 +1519,154 @@ mod tests {
         assert_eq!(&res_m_relin, &m0);
     }
 
+    #[test]
+    fn test_non_ntt_friendly_plaintext_modulus() {
+        let mut rng = thread_rng();
+
+        // power of two and small composite plaintext moduli
+        for t in [1 << 16, 1000] {
+            let params = BfvParameters::new(&[50; 3], t, 1 << 4);
+            assert!(!params.supports_batching());
+
+            // gen keys
+            let sk = SecretKey::random(params.degree, params.hw, &mut rng);
+            let ek = EvaluationKey::new(&params, &sk, &[0], &[], &[], &mut rng);
+
+            let m0 = params
+                .plaintext_modulus_op
+                .random_vec(params.degree, &mut rng);
+            let m1 = params
+                .plaintext_modulus_op
+                .random_vec(params.degree, &mut rng);
+
+            // m0 * m1 mod (X^N + 1, t)
+            let modt = &params.plaintext_modulus_op;
+            let mut expected_m = vec![0u64; params.degree];
+            for (i, a) in m0.iter().enumerate() {
+                for (j, b) in m1.iter().enumerate() {
+                    let v = modt.mul_mod_fast(*a, *b);
+                    let k = (i + j) % params.degree;
+                    if i + j < params.degree {
+                        expected_m[k] = modt.add_mod_fast(expected_m[k], v);
+                    } else {
+                        expected_m[k] = modt.sub_mod_fast(expected_m[k], v);
+                    }
+                }
+            }
+
+            let evaluator = Evaluator::new(params);
+            let poly_encoding = Encoding::poly(0, PolyCache::None);
+
+            // SIMD encoding is not supported
+            assert_eq!(
+                evaluator
+                    .try_plaintext_encode(&m0, Encoding::default())
+                    .err(),
+                Some(BfvError::BatchingNotSupported {
+                    plaintext_modulus: t,
+                    degree: 1 << 4
+                })
+            );
+
+            let pt0 = evaluator.plaintext_encode(&m0, poly_encoding.clone());
+            let pt1 = evaluator.plaintext_encode(&m1, poly_encoding.clone());
+            let ct0 = evaluator.encrypt(&sk, &pt0, &mut rng);
+            let ct1 = evaluator.encrypt(&sk, &pt1, &mut rng);
+
+            // decrypted plaintext records encoding
+            let pt0_dec = evaluator.decrypt(&sk, &ct0);
+            assert!(pt0_dec.encoding().unwrap().encoding_type() == EncodingType::Poly);
+            assert_eq!(
+                evaluator.try_plaintext_decode(&pt0_dec, Encoding::default()),
+                Err(BfvError::EncodingMismatch {
+                    expected: EncodingType::Poly,
+                    found: EncodingType::Simd
+                })
+            );
+            let res_m = evaluator.plaintext_decode(&pt0_dec, poly_encoding.clone());
+            assert_eq!(res_m, m0);
+
+            let ct01 = evaluator.relinearize(&evaluator.mul(&ct0, &ct1), &ek);
+            assert!(evaluator.noise_budget(&sk, &ct01) > 0);
+            let res_m =
+                evaluator.plaintext_decode(&evaluator.decrypt(&sk, &ct01), poly_encoding.clone());
+            assert_eq!(res_m, expected_m);
+        }
+    }
+
+    #[test]
+    fn test_mul_deferred_relinearize() {
+        let mut rng = thread_rng();
+        let mut params = BfvParameters::new(&[50; 6], 65537, 1 << 4);
+        let bv_params = params.clone();
+        params.enable_hybrid_key_switching(&[50, 50, 50]);
+
+        for params in [params, bv_params] {
+            // gen keys
+            let sk = SecretKey::random(params.degree, params.hw, &mut rng);
+            let ek =
+                EvaluationKey::new_with_rlk_max_degree(&params, &sk, &[0], 3, &[], &[], &mut rng);
+
+            let mut m0 = params
+                .plaintext_modulus_op
+                .random_vec(params.degree, &mut rng);
+            let m1 = params
+                .plaintext_modulus_op
+                .random_vec(params.degree, &mut rng);
+            let m2 = params
+                .plaintext_modulus_op
+                .random_vec(params.degree, &mut rng);
+
+            let evaluator = Evaluator::new(params);
+            let ct0 = evaluator.encrypt(
+                &sk,
+                &evaluator.plaintext_encode(&m0, Encoding::default()),
+                &mut rng,
+            );
+            let ct1 = evaluator.encrypt(
+                &sk,
+                &evaluator.plaintext_encode(&m1, Encoding::default()),
+                &mut rng,
+            );
+            let ct2 = evaluator.encrypt(
+                &sk,
+                &evaluator.plaintext_encode(&m2, Encoding::default()),
+                &mut rng,
+            );
+
+            // m0 = m0 * m1 * m2
+            evaluator
+                .params
+                .plaintext_modulus_op
+                .mul_mod_fast_vec(&mut m0, &m1);
+            evaluator
+                .params
+                .plaintext_modulus_op
+                .mul_mod_fast_vec(&mut m0, &m2);
+
+            // multiply without relinearizing in between
+            let ct01 = evaluator.mul(&ct0, &ct1);
+            let ct012 = evaluator.mul(&ct01, &ct2);
+            assert!(ct012.c.len() == 4);
+            assert!(evaluator.noise_budget(&sk, &ct012) > 0);
+
+            let res_m =
+                evaluator.plaintext_decode(&evaluator.decrypt(&sk, &ct012), Encoding::default());
+            assert_eq!(&res_m, &m0);
+
+            // tensoring ciphertexts with 3 polynomials gives ciphertext with 5 polynomials
+            assert!(evaluator.mul_lazy(&ct01, &ct01).c.len() == 5);
+
+            let ct012_relin = evaluator.relinearize(&ct012, &ek);
+            assert!(evaluator.noise_budget(&sk, &ct012_relin) > 0);
+            let res_m_relin = evaluator
+                .plaintext_decode(&evaluator.decrypt(&sk, &ct012_relin), Encoding::default());
+
+            assert!(ct012_relin.c.len() == 2);
+            assert_eq!(&res_m_relin, &m0);
+        }
+    }
+
     #[test]
     fn test_add_sub_plaintext() {
         let mut rng = thread_rng();
@@ -549,6 +1714,57 @@ mod tests {
         assert_eq!(res_sub, expected_sub);
     }
 
+    #[test]
+    fn test_scalar_ops() {
+        let mut rng = thread_rng();
+        let params = BfvParameters::default(5, 1 << 4);
+
+        // gen keys
+        let sk = SecretKey::random(params.degree, params.hw, &mut rng);
+
+        let m0 = params
+            .plaintext_modulus_op
+            .random_vec(params.degree, &mut rng);
+        let scalar = rng.gen_range(0..params.plaintext_modulus);
+
+        let evaluator = Evaluator::new(params);
+        let modt = &evaluator.params.plaintext_modulus_op;
+        let pt0 = evaluator.plaintext_encode(&m0, Encoding::default());
+
+        let mut ct = evaluator.encrypt(&sk, &pt0, &mut rng);
+        evaluator.mod_down_next(&mut ct);
+
+        let mut expected_mul = m0.clone();
+        modt.scalar_mul_mod_fast_vec(&mut expected_mul, scalar);
+        let expected_add = m0
+            .iter()
+            .map(|v| modt.add_mod_fast(*v, scalar))
+            .collect_vec();
+        let expected_sub = m0
+            .iter()
+            .map(|v| modt.sub_mod_fast(*v, scalar))
+            .collect_vec();
+
+        for representation in [Representation::Coefficient, Representation::Evaluation] {
+            evaluator.ciphertext_change_representation(&mut ct, representation.clone());
+
+            let mut ct_mul = evaluator.mul_scalar(&ct, scalar);
+            let mut ct_add = evaluator.add_scalar(&ct, scalar);
+            let mut ct_sub = evaluator.sub_scalar(&ct, scalar);
+            for (c, expected) in [
+                (&mut ct_mul, &expected_mul),
+                (&mut ct_add, &expected_add),
+                (&mut ct_sub, &expected_sub),
+            ] {
+                evaluator.ciphertext_change_representation(c, Representation::Coefficient);
+                assert!(evaluator.noise_budget(&sk, c) > 0);
+                let res =
+                    evaluator.plaintext_decode(&evaluator.decrypt(&sk, c), Encoding::default());
+                assert_eq!(&res, expected);
+            }
+        }
+    }
+
     #[test]
     fn test_mul_poly() {
         let mut rng = thread_rng();
@@ -583,6 +1799,7 @@ mod tests {
         let pt1 = evaluator.plaintext_encode(&m1, Encoding::simd(0, PolyCache::Mul(PolyType::PQ)));
         let ct_lazy = evaluator.mul_lazy(&ct, &ct);
         let mut res_ct = evaluator.mul_plaintext(&ct_lazy, &pt1);
+        assert!(evaluator.noise_budget(&sk, &res_ct) > 0);
         let res_ct = evaluator.scale_and_round(&mut res_ct);
         let res = evaluator.plaintext_decode(&evaluator.decrypt(&sk, &res_ct), Encoding::default());
         // m0^2 * m1
@@ -615,6 +1832,7 @@ mod tests {
         println!("Noise original: {}", evaluator.measure_noise(&sk, &ct0,));
 
         let ct_rotated = evaluator.rotate(&ct0, 1, &ek);
+        assert!(evaluator.noise_budget(&sk, &ct_rotated) > 0);
 
         // decrypt ct01
         let res_m =
@@ -622,6 +1840,118 @@ mod tests {
         dbg!(&res_m, &m0);
     }
 
+    #[test]
+    fn test_power_of_two_rotations() {
+        let mut rng = thread_rng();
+        let params = BfvParameters::default(3, 1 << 4);
+
+        // gen keys
+        let sk = SecretKey::random(params.degree, params.hw, &mut rng);
+        let ek = EvaluationKey::new_with_power_of_two_rotations(&params, &sk, &[], &[0], &mut rng);
+        // 1, -1, 2, -2, 4 and row swap
+        assert_eq!(ek.rtgs.len(), 6);
+
+        // ek with galois keys for every rotation to check against
+        let row_size = (params.degree / 2) as isize;
+        let indices = (1 - row_size..row_size).filter(|i| *i != 0).collect_vec();
+        let full_ek = EvaluationKey::new(
+            &params,
+            &sk,
+            &[],
+            &vec![0; indices.len()],
+            &indices,
+            &mut rng,
+        );
+
+        let m0 = params
+            .plaintext_modulus_op
+            .random_vec(params.degree, &mut rng);
+
+        let evaluator = Evaluator::new(params);
+        let pt0 = evaluator.plaintext_encode(&m0, Encoding::default());
+        let ct0 = evaluator.encrypt(&sk, &pt0, &mut rng);
+
+        for rotate_by in -20..20 {
+            let ct_rotated = evaluator.rotate(&ct0, rotate_by, &ek);
+            let res_m = evaluator
+                .plaintext_decode(&evaluator.decrypt(&sk, &ct_rotated), Encoding::default());
+
+            let r = rotate_by.rem_euclid(row_size);
+            let expected_m = if r == 0 {
+                m0.clone()
+            } else {
+                evaluator.plaintext_decode(
+                    &evaluator.decrypt(&sk, &evaluator.rotate(&ct0, r, &full_ek)),
+                    Encoding::default(),
+                )
+            };
+            assert_eq!(res_m, expected_m, "rotate_by {rotate_by}");
+        }
+
+        // missing decomposition keys
+        let ek = EvaluationKey::new(evaluator.params(), &sk, &[], &[0], &[1], &mut rng);
+        assert_eq!(
+            evaluator.try_rotate(&ct0, 2, &ek),
+            Err(BfvError::MissingGaloisKey {
+                rotate_by: 2,
+                level: 0
+            })
+        );
+    }
+
+    #[test]
+    fn bv_key_switching() {
+        let mut rng = thread_rng();
+        let params = BfvParameters::new(&[50; 4], 65537, 1 << 4);
+        assert_eq!(params.key_switching_method(), KeySwitchingMethod::BV);
+
+        // hybrid params with same ciphertext moduli to check rotations against
+        let mut hybrid_params = params.clone();
+        hybrid_params.enable_hybrid_key_switching(&[50, 50, 50]);
+
+        // gen keys
+        let sk = SecretKey::random(params.degree, params.hw, &mut rng);
+        let ek = EvaluationKey::new(&params, &sk, &[0], &[0], &[1], &mut rng);
+        let hybrid_ek = EvaluationKey::new(&hybrid_params, &sk, &[], &[0], &[1], &mut rng);
+
+        let mut m0 = params
+            .plaintext_modulus_op
+            .random_vec(params.degree, &mut rng);
+        let m1 = params
+            .plaintext_modulus_op
+            .random_vec(params.degree, &mut rng);
+
+        let evaluator = Evaluator::new(params);
+        let hybrid_evaluator = Evaluator::new(hybrid_params);
+        let pt0 = evaluator.plaintext_encode(&m0, Encoding::default());
+        let pt1 = evaluator.plaintext_encode(&m1, Encoding::default());
+        let ct0 = evaluator.encrypt(&sk, &pt0, &mut rng);
+        let ct1 = evaluator.encrypt(&sk, &pt1, &mut rng);
+
+        // rotate
+        let ct_rotated = evaluator.rotate(&ct0, 1, &ek);
+        let ct_rotated_hybrid = hybrid_evaluator.rotate(&ct0, 1, &hybrid_ek);
+        assert!(evaluator.noise_budget(&sk, &ct_rotated) > 0);
+        assert!(hybrid_evaluator.noise_budget(&sk, &ct_rotated_hybrid) > 0);
+        assert_eq!(
+            evaluator.plaintext_decode(&evaluator.decrypt(&sk, &ct_rotated), Encoding::default()),
+            evaluator.plaintext_decode(
+                &evaluator.decrypt(&sk, &ct_rotated_hybrid),
+                Encoding::default()
+            )
+        );
+
+        // mul and relinearize
+        let ct01 = evaluator.relinearize(&evaluator.mul(&ct0, &ct1), &ek);
+        assert!(evaluator.noise_budget(&sk, &ct01) > 0);
+        evaluator
+            .params
+            .plaintext_modulus_op
+            .mul_mod_fast_vec(&mut m0, &m1);
+        let res_m = evaluator.plaintext_decode(&evaluator.decrypt(&sk, &ct01), Encoding::default());
+        assert_eq!(res_m, m0);
+    }
+
     #[test]
     #[ignore = "Takes long because degree is set to 2^15"]
     fn test_mul_lazy_add_and_relinearize() {
@@ -675,6 +2005,9 @@ mod tests {
             evaluator.measure_noise(&sk, &c_res)
         );
 
+        assert!(evaluator.noise_budget(&sk, &c_res_lazy) > 0);
+        assert!(evaluator.noise_budget(&sk, &c_res) > 0);
+
         println!("Time: Lazy={:?}  Normal:{:?}", lazy_time, normal_time);
     }
 
@@ -717,5 +2050,502 @@ mod tests {
         let noise_before = evaluator.measure_noise(&sk, &ct0);
         evaluator.mod_down_next(&mut ct0);
         assert!(evaluator.measure_noise(&sk, &ct0) <= noise_before);
+        assert!(evaluator.noise_budget(&sk, &ct0) > 0);
+    }
+    #[test]
+    fn try_ops_return_errors() {
+        let mut rng = thread_rng();
+        let params = BfvParameters::default(3, 1 << 4);
+
+        // gen keys
+        let sk = SecretKey::random(params.degree, params.hw, &mut rng);
+        let ek = EvaluationKey::new(&params, &sk, &[], &[], &[], &mut rng);
+
+        let m0 = params
+            .plaintext_modulus_op
+            .random_vec(params.degree, &mut rng);
+        let evaluator = Evaluator::new(params);
+        let pt0 = evaluator.plaintext_encode(&m0, Encoding::default());
+        let pt1 = evaluator.plaintext_encode(
+            &m0,
+            Encoding::simd(1, PolyCache::AddSub(Representation::Coefficient)),
+        );
+        let ct0 = evaluator.encrypt(&sk, &pt0, &mut rng);
+        let mut ct1 = ct0.clone();
+        evaluator.mod_down_next(&mut ct1);
+
+        assert_eq!(
+            evaluator.try_add(&ct0, &ct1),
+            Err(BfvError::LevelMismatch {
+                expected: 0,
+                found: 1
+            })
+        );
+        assert_eq!(
+            evaluator.try_add_plaintext(&ct0, &pt1),
+            Err(BfvError::LevelMismatch {
+                expected: 1,
+                found: 0
+            })
+        );
+        assert_eq!(
+            evaluator.try_mul_plaintext(&ct1, &pt1),
+            Err(BfvError::MissingMulPoly)
+        );
+        assert_eq!(
+            evaluator.try_rotate(&ct0, 1, &ek),
+            Err(BfvError::MissingGaloisKey {
+                rotate_by: 1,
+                level: 0
+            })
+        );
+
+        let ct00 = evaluator.mul(&ct0, &ct0);
+        assert_eq!(
+            evaluator.try_relinearize(&ct00, &ek),
+            Err(BfvError::MissingRelinearizationKey { level: 0 })
+        );
+
+        // rlk with default max. degree cannot relinearize ciphertext with 4 polynomials
+        let ek = EvaluationKey::new(evaluator.params(), &sk, &[0], &[], &[], &mut rng);
+        let ct000 = evaluator.mul(&ct00, &ct0);
+        assert_eq!(
+            evaluator.try_relinearize(&ct000, &ek),
+            Err(BfvError::RelinearizationKeyDegreeTooSmall {
+                max_degree: 2,
+                degree: 3
+            })
+        );
+
+        // cannot mod down beyond max level
+        let mut ct2 = ct1.clone();
+        evaluator.mod_down_next(&mut ct2);
+        assert!(evaluator.try_mod_down_next(&mut ct2).is_err());
+    }
+
+    #[test]
+    fn noise_estimate_bounds_measured_noise() {
+        let mut rng = thread_rng();
+        let params = BfvParameters::default(6, 1 << 4);
+
+        // gen keys
+        let sk = SecretKey::random(params.degree, params.hw, &mut rng);
+        let pk = PublicKey::new(&params, &sk, 0, &mut rng);
+        let ek = EvaluationKey::new(&params, &sk, &[0], &[0], &[1], &mut rng);
+
+        let m0 = params
+            .plaintext_modulus_op
+            .random_vec(params.degree, &mut rng);
+        let m1 = params
+            .plaintext_modulus_op
+            .random_vec(params.degree, &mut rng);
+
+        let evaluator = Evaluator::new(params);
+        let pt0 = evaluator.plaintext_encode(&m0, Encoding::default());
+        let pt1 = evaluator.plaintext_encode(&m1, Encoding::simd(0, PolyCache::Mul(PolyType::Q)));
+        let ct0 = evaluator.encrypt(&sk, &pt0, &mut rng);
+        let ct1 = evaluator.encrypt_public(&pk, &pt0, &mut rng);
+
+        let check = |ct: &Ciphertext| {
+            // measured noise is number of bits of ||v||, which is at most log2(||v||) + 1
+            let estimate = ct.noise_estimate().unwrap();
+            let measured = evaluator.measure_noise(&sk, ct);
+            assert!(
+                measured as f64 <= estimate.bits() + 1.0,
+                "measured noise {measured} exceeds estimate {}",
+                estimate.bits()
+            );
+            assert!(evaluator.estimated_noise_budget(ct).unwrap() > 0.0);
+        };
+
+        check(&ct0);
+        check(&ct1);
+        check(&evaluator.add(&ct0, &ct1));
+        check(&evaluator.mul_plaintext(&ct0, &pt1));
+
+        let mut ct_scalar = ct0.clone();
+        evaluator.mul_scalar_assign(&mut ct_scalar, 1234);
+        check(&ct_scalar);
+
+        let ct01 = evaluator.relinearize(&evaluator.mul(&ct0, &ct1), &ek);
+        check(&ct01);
+        check(&evaluator.rotate(&ct01, 1, &ek));
+
+        let mut ct_down = ct01.clone();
+        evaluator.mod_down_next(&mut ct_down);
+        check(&ct_down);
+
+        // noise of the product must exceed the noise of its inputs
+        assert!(ct01.noise_estimate().unwrap() > ct0.noise_estimate().unwrap());
+        assert!(
+            evaluator.estimated_noise_budget(&ct_down).unwrap()
+                < evaluator.estimated_noise_budget(&ct0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_sum_slots() {
+        let mut rng = thread_rng();
+        let params = BfvParameters::default(3, 1 << 4);
+        let degree = params.degree;
+        let row_size = degree / 2;
+
+        // gen keys
+        let sk = SecretKey::random(params.degree, params.hw, &mut rng);
+        let ek =
+            EvaluationKey::new_with_sum_slots_rotations(&params, &sk, &[], &[0], degree, &mut rng);
+        // 1, 2, 4 and row swap
+        assert_eq!(ek.rtgs.len(), 4);
+
+        let m0 = params
+            .plaintext_modulus_op
+            .random_vec(params.degree, &mut rng);
+
+        let evaluator = Evaluator::new(params);
+        let modt = &evaluator.params.plaintext_modulus_op;
+        let pt0 = evaluator.plaintext_encode(&m0, Encoding::default());
+        let ct0 = evaluator.encrypt(&sk, &pt0, &mut rng);
+
+        for width in [1, 2, 4, 8, 16] {
+            let ct_sum = evaluator.sum_slots(&ct0, width, &ek);
+            assert!(evaluator.noise_budget(&sk, &ct_sum) > 0);
+            let res_m =
+                evaluator.plaintext_decode(&evaluator.decrypt(&sk, &ct_sum), Encoding::default());
+
+            let expected_m = (0..degree)
+                .map(|i| {
+                    let (row, col) = (i / row_size, i % row_size);
+                    let row_sum = (0..std::cmp::min(width, row_size)).fold(0, |acc, j| {
+                        modt.add_mod_fast(acc, m0[row * row_size + (col + j) % row_size])
+                    });
+                    if width == degree {
+                        // sum of the other row
+                        (0..row_size).fold(row_sum, |acc, j| {
+                            modt.add_mod_fast(acc, m0[(1 - row) * row_size + j])
+                        })
+                    } else {
+                        row_sum
+                    }
+                })
+                .collect_vec();
+            assert_eq!(res_m, expected_m, "width {width}");
+        }
+
+        // every slot holds sum of all slots
+        let res_m = evaluator.plaintext_decode(
+            &evaluator.decrypt(&sk, &evaluator.inner_sum(&ct0, &ek)),
+            Encoding::default(),
+        );
+        let sum = m0.iter().fold(0, |acc, v| modt.add_mod_fast(acc, *v));
+        assert_eq!(res_m, vec![sum; degree]);
+
+        assert_eq!(
+            evaluator.try_sum_slots(&ct0, 3, &ek),
+            Err(BfvError::InvalidSumWidth { width: 3, degree })
+        );
+        assert_eq!(
+            EvaluationKey::try_new_with_sum_slots_rotations(
+                evaluator.params(),
+                &sk,
+                &[],
+                &[0],
+                2 * degree,
+                &mut rng,
+            ),
+            Err(BfvError::InvalidSumWidth {
+                width: 2 * degree,
+                degree
+            })
+        );
+
+        // ek for smaller width does not have row swap
+        let ek = EvaluationKey::new_with_sum_slots_rotations(
+            evaluator.params(),
+            &sk,
+            &[],
+            &[0],
+            row_size,
+            &mut rng,
+        );
+        assert_eq!(ek.rtgs.len(), 3);
+        assert!(evaluator.try_sum_slots(&ct0, row_size, &ek).is_ok());
+        assert!(evaluator.try_inner_sum(&ct0, &ek).is_err());
+    }
+
+    #[test]
+    fn test_rotate_many() {
+        let mut rng = thread_rng();
+        let params = BfvParameters::new(&[50; 4], 65537, 1 << 4);
+        let row_swap = (2 * params.degree - 1) as isize;
+
+        let mut hybrid_params = params.clone();
+        hybrid_params.enable_hybrid_key_switching(&[50, 50, 50]);
+
+        let sk = SecretKey::random(params.degree, params.hw, &mut rng);
+        let m0 = params
+            .plaintext_modulus_op
+            .random_vec(params.degree, &mut rng);
+
+        for params in [params, hybrid_params] {
+            // no galois key for 3, thus it falls back to power-of-two rotations
+            let ek = EvaluationKey::new(
+                &params,
+                &sk,
+                &[],
+                &[0; 5],
+                &[1, -1, 2, 4, row_swap],
+                &mut rng,
+            );
+
+            let evaluator = Evaluator::new(params);
+            let pt0 = evaluator.plaintext_encode(&m0, Encoding::default());
+            let mut ct0 = evaluator.encrypt(&sk, &pt0, &mut rng);
+
+            for representation in [Representation::Coefficient, Representation::Evaluation] {
+                evaluator.ciphertext_change_representation(&mut ct0, representation);
+
+                let rotations = [0, 1, -1, 2, 3, 4, row_swap];
+                let cts = evaluator.rotate_many(&ct0, &rotations, &ek);
+                assert_eq!(cts.len(), rotations.len());
+                izip!(rotations.iter(), cts.iter()).for_each(|(rotate_by, ct)| {
+                    assert!(evaluator.noise_budget(&sk, ct) > 0);
+                    assert_eq!(
+                        evaluator
+                            .plaintext_decode(&evaluator.decrypt(&sk, ct), Encoding::default()),
+                        evaluator.plaintext_decode(
+                            &evaluator.decrypt(&sk, &evaluator.rotate(&ct0, *rotate_by, &ek)),
+                            Encoding::default()
+                        ),
+                        "rotate_by {rotate_by}"
+                    );
+                });
+            }
+
+            assert_eq!(
+                evaluator.try_rotate_many(&ct0, &[1, 6], &ek),
+                Err(BfvError::MissingGaloisKey {
+                    rotate_by: 6,
+                    level: 0
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn bv_keys_with_hybrid_params() {
+        let mut rng = thread_rng();
+        let mut params = BfvParameters::new(&[50; 4], 65537, 1 << 4);
+        let degree = params.degree;
+        let row_size = degree / 2;
+        let sk = SecretKey::random(params.degree, params.hw, &mut rng);
+
+        // keys switch with BV since they are generated before hybrid key switching is enabled
+        let ek = EvaluationKey::new(&params, &sk, &[0], &[0; 2], &[1, 2], &mut rng);
+        params.enable_hybrid_key_switching(&[50, 50, 50]);
+
+        let m0 = params
+            .plaintext_modulus_op
+            .random_vec(params.degree, &mut rng);
+        let m1 = params
+            .plaintext_modulus_op
+            .random_vec(params.degree, &mut rng);
+
+        let evaluator = Evaluator::new(params);
+        let modt = &evaluator.params.plaintext_modulus_op;
+        let ct0 = evaluator.encrypt(
+            &sk,
+            &evaluator.plaintext_encode(&m0, Encoding::default()),
+            &mut rng,
+        );
+        let ct1 = evaluator.encrypt(
+            &sk,
+            &evaluator.plaintext_encode(&m1, Encoding::default()),
+            &mut rng,
+        );
+
+        let ct_res = evaluator.relinearize(&evaluator.mul(&ct0, &ct1), &ek);
+        assert!(evaluator.noise_budget(&sk, &ct_res) > 0);
+        assert_eq!(
+            evaluator.plaintext_decode(&evaluator.decrypt(&sk, &ct_res), Encoding::default()),
+            izip!(m0.iter(), m1.iter())
+                .map(|(a, b)| modt.mul_mod_fast(*a, *b))
+                .collect_vec()
+        );
+
+        // 3 falls back to rotations by 1 and 2
+        let rotations = [1, 2, 3];
+        let cts = evaluator.rotate_many(&ct0, &rotations, &ek);
+        izip!(rotations.iter(), cts.iter()).for_each(|(rotate_by, ct)| {
+            assert!(evaluator.noise_budget(&sk, ct) > 0);
+            let expected_m = (0..degree)
+                .map(|i| {
+                    let (row, col) = (i / row_size, i % row_size);
+                    m0[row * row_size + (col + *rotate_by as usize) % row_size]
+                })
+                .collect_vec();
+            assert_eq!(
+                evaluator.plaintext_decode(&evaluator.decrypt(&sk, ct), Encoding::default()),
+                expected_m,
+                "rotate_by {rotate_by}"
+            );
+        });
+    }
+
+    #[test]
+    fn test_key_switching_in_qp() {
+        let mut rng = thread_rng();
+        let params = BfvParameters::new(&[50; 4], 65537, 1 << 4);
+
+        let mut hybrid_params = params.clone();
+        hybrid_params.enable_hybrid_key_switching(&[50, 50, 50]);
+
+        let sk = SecretKey::random(params.degree, params.hw, &mut rng);
+        let m0 = params
+            .plaintext_modulus_op
+            .random_vec(params.degree, &mut rng);
+
+        for params in [params, hybrid_params] {
+            let rotations = [1, 2, 3];
+            let ek = EvaluationKey::new_with_rlk_max_degree(
+                &params,
+                &sk,
+                &[0],
+                3,
+                &[0; 3],
+                &rotations,
+                &mut rng,
+            );
+
+            let evaluator = Evaluator::new(params);
+            let pt0 = evaluator.plaintext_encode(&m0, Encoding::default());
+            let ct0 = evaluator.encrypt(&sk, &pt0, &mut rng);
+
+            // sum of rotations
+            let mut cts = evaluator.rotate_many_qp(&ct0, &rotations, &ek).into_iter();
+            let mut sum_qp = cts.next().unwrap();
+            cts.for_each(|ct| evaluator.add_assign_qp(&mut sum_qp, &ct));
+            let sum = evaluator.mod_down_qp(sum_qp);
+            assert!(sum.c_ref()[0].representation == Representation::Coefficient);
+            assert!(evaluator.noise_budget(&sk, &sum) > 0);
+
+            let mut expected = evaluator.rotate(&ct0, 1, &ek);
+            evaluator.add_assign(&mut expected, &evaluator.rotate(&ct0, 2, &ek));
+            evaluator.add_assign(&mut expected, &evaluator.rotate(&ct0, 3, &ek));
+            assert_eq!(
+                evaluator.plaintext_decode(&evaluator.decrypt(&sk, &sum), Encoding::default()),
+                evaluator.plaintext_decode(&evaluator.decrypt(&sk, &expected), Encoding::default())
+            );
+
+            // relinearization
+            let ct012 = evaluator.mul(&evaluator.mul(&ct0, &ct0), &ct0);
+            let ct_relin = evaluator.mod_down_qp(evaluator.relinearize_qp(&ct012, &ek));
+            assert_eq!(ct_relin, evaluator.relinearize(&ct012, &ek));
+            assert_eq!(
+                evaluator.plaintext_decode(&evaluator.decrypt(&sk, &ct_relin), Encoding::default()),
+                evaluator.plaintext_decode(&evaluator.decrypt(&sk, &ct012), Encoding::default())
+            );
+
+            // rotation cannot be decomposed in QP
+            assert_eq!(
+                evaluator.try_rotate_qp(&ct0, 4, &ek).unwrap_err(),
+                BfvError::MissingGaloisKey {
+                    rotate_by: 4,
+                    level: 0
+                }
+            );
+        }
+
+        // BV key generated before hybrid key switching is enabled is key switched (and switched to Q)
+        // with BV, thus its output cannot be accumulated with output of hybrid key
+        let mut params = BfvParameters::new(&[50; 4], 65537, 1 << 4);
+        let bv_ek = EvaluationKey::new(&params, &sk, &[], &[0], &[1], &mut rng);
+        params.enable_hybrid_key_switching(&[50, 50, 50]);
+        let hybrid_ek = EvaluationKey::new(&params, &sk, &[], &[0], &[1], &mut rng);
+
+        let evaluator = Evaluator::new(params);
+        let pt0 = evaluator.plaintext_encode(&m0, Encoding::default());
+        let ct0 = evaluator.encrypt(&sk, &pt0, &mut rng);
+
+        let mut bv_ct = evaluator.rotate_qp(&ct0, 1, &bv_ek);
+        let hybrid_ct = evaluator.rotate_qp(&ct0, 1, &hybrid_ek);
+        assert_eq!(bv_ct.key_switching_method(), KeySwitchingMethod::BV);
+        assert_eq!(hybrid_ct.key_switching_method(), KeySwitchingMethod::Hybrid);
+        assert_eq!(
+            evaluator.try_add_assign_qp(&mut bv_ct, &hybrid_ct),
+            Err(BfvError::KeySwitchingMethodMismatch {
+                expected: KeySwitchingMethod::BV,
+                found: KeySwitchingMethod::Hybrid
+            })
+        );
+
+        let expected = evaluator.plaintext_decode(
+            &evaluator.decrypt(&sk, &evaluator.rotate(&ct0, 1, &hybrid_ek)),
+            Encoding::default(),
+        );
+        for ct in [bv_ct, hybrid_ct] {
+            let ct = evaluator.mod_down_qp(ct);
+            assert!(evaluator.noise_budget(&sk, &ct) > 0);
+            assert_eq!(
+                evaluator.plaintext_decode(&evaluator.decrypt(&sk, &ct), Encoding::default()),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_evaluate_polynomial() {
+        let mut rng = thread_rng();
+        let params = BfvParameters::default(10, 1 << 4);
+        let sk = SecretKey::random(params.degree, params.hw, &mut rng);
+        let ek = EvaluationKey::new(&params, &sk, &[0, 1], &[], &[], &mut rng);
+
+        let m0 = params
+            .plaintext_modulus_op
+            .random_vec(params.degree, &mut rng);
+
+        let evaluator = Evaluator::new(params);
+        let modt = &evaluator.params.plaintext_modulus_op;
+        let pt0 = evaluator.plaintext_encode(&m0, Encoding::default());
+        let ct0 = evaluator.encrypt(&sk, &pt0, &mut rng);
+        let mut ct0_level1 = ct0.clone();
+        evaluator.mod_down_next(&mut ct0_level1);
+
+        for ct in [&ct0, &ct0_level1] {
+            for degree in [0, 1, 2, 3, 4, 5, 8, 15] {
+                let coeffs = modt.random_vec(degree + 1, &mut rng);
+                let ct_res = evaluator.evaluate_polynomial(ct, &coeffs, &ek);
+                assert_eq!(ct_res.level(), ct.level());
+                assert!(evaluator.noise_budget(&sk, &ct_res) > 0);
+                let res_m = evaluator
+                    .plaintext_decode(&evaluator.decrypt(&sk, &ct_res), Encoding::default());
+
+                // horner
+                let expected_m = m0
+                    .iter()
+                    .map(|x| {
+                        coeffs.iter().rev().fold(0, |acc, c| {
+                            modt.add_mod_fast(modt.mul_mod_fast(acc, *x), *c)
+                        })
+                    })
+                    .collect_vec();
+                assert_eq!(res_m, expected_m, "degree {degree}");
+            }
+        }
+
+        // polynomials of degree < 2 do not require relinearization key
+        let ek = EvaluationKey::new(evaluator.params(), &sk, &[], &[], &[], &mut rng);
+        let ct_res = evaluator.evaluate_polynomial(&ct0, &[3, 2, 0], &ek);
+        let res_m =
+            evaluator.plaintext_decode(&evaluator.decrypt(&sk, &ct_res), Encoding::default());
+        let expected_m = m0
+            .iter()
+            .map(|x| modt.add_mod_fast(modt.mul_mod_fast(*x, 2), 3))
+            .collect_vec();
+        assert_eq!(res_m, expected_m);
+
+        assert_eq!(
+            evaluator.try_evaluate_polynomial(&ct0, &[3, 2, 1], &ek),
+            Err(BfvError::MissingRelinearizationKey { level: 0 })
+        );
     }
 }
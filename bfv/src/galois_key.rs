@@ -1,13 +1,14 @@
 use crate::{
-    BfvParameters, Ciphertext, HybridKeySwitchingKey, Modulus, Poly, PolyContext, PolyType,
+    BfvParameters, Ciphertext, KeySwitchingKey, Modulus, Poly, PolyContext, PolyType, QpCiphertext,
     Representation, SecretKey, Substitution,
 };
+use itertools::Itertools;
 use rand::{CryptoRng, RngCore};
 
 #[derive(Debug, PartialEq)]
 pub struct GaloisKey {
     pub(crate) substitution: Substitution,
-    pub(crate) ksk_key: HybridKeySwitchingKey,
+    pub(crate) ksk_key: KeySwitchingKey,
     pub(crate) level: usize,
 }
 
@@ -22,7 +23,6 @@ impl GaloisKey {
         let substitution = Substitution::new(exponent, params.degree);
 
         let q_ctx = params.poly_ctx(&PolyType::Q, level);
-        let qp_ctx = params.poly_ctx(&PolyType::QP, level);
 
         // Substitute secret key
         let mut sk_poly =
@@ -32,14 +32,7 @@ impl GaloisKey {
         let sk_poly = q_ctx.substitute(&sk_poly, &substitution);
 
         // Generate key switching key for substituted secret key
-        let ksk_key = HybridKeySwitchingKey::new(
-            params.hybrid_key_switching_params_at_level(level),
-            &sk_poly,
-            &sk,
-            &qp_ctx,
-            params.variance,
-            rng,
-        );
+        let ksk_key = KeySwitchingKey::new(params, &sk_poly, sk, level, rng);
 
         GaloisKey {
             substitution,
@@ -55,8 +48,6 @@ impl GaloisKey {
 
         let level = self.level;
         let q_ctx = params.poly_ctx(&PolyType::Q, level);
-        let qp_ctx = params.poly_ctx(&PolyType::QP, level);
-        let specialp_ctx = params.poly_ctx(&PolyType::SpecialP, level);
 
         // Key switch c1
         let mut c1 = q_ctx.substitute(&ct.c[1], &self.substitution);
@@ -64,13 +55,83 @@ impl GaloisKey {
             q_ctx.change_representation(&mut c1, Representation::Coefficient);
         }
 
-        let (mut cs0, mut cs1) = self.ksk_key.switch(
-            params.hybrid_key_switching_params_at_level(level),
-            &c1,
-            &qp_ctx,
-            &q_ctx,
-            &specialp_ctx,
-        );
+        let (cs0, cs1) = self.ksk_key.switch(params, &c1, level);
+        self.finish_rotation(ct, cs0, cs1, params)
+    }
+
+    /// Same as `rotate` but uses `c1_parts`, decomposition of ciphertext's c1 (see
+    /// `KeySwitchingKey::decompose`), instead of decomposing substituted c1. Decomposition is computed
+    /// once for all rotations of a ciphertext (ie hoisting).
+    pub fn rotate_decomposed(
+        &self,
+        ct: &Ciphertext,
+        c1_parts: &[Poly],
+        params: &BfvParameters,
+    ) -> Ciphertext {
+        assert!(ct.c.len() == 2);
+        assert!(ct.level == self.level);
+        assert!(ct.poly_type == PolyType::Q);
+
+        let level = self.level;
+        let ctx = self.ksk_key.decomposition_ctx(params, level);
+
+        // substitute parts instead of c1
+        let parts = c1_parts
+            .iter()
+            .map(|p| ctx.substitute(p, &self.substitution))
+            .collect_vec();
+
+        let (cs0, cs1) = self.ksk_key.switch_decomposed(params, &parts, level);
+        self.finish_rotation(ct, cs0, cs1, params)
+    }
+
+    /// Same as `rotate_decomposed` but returns rotated ciphertext with key switched polynomials in QP (see
+    /// `QpCiphertext`)
+    pub fn rotate_decomposed_qp(
+        &self,
+        ct: &Ciphertext,
+        c1_parts: &[Poly],
+        params: &BfvParameters,
+    ) -> QpCiphertext {
+        assert!(ct.c.len() == 2);
+        assert!(ct.level == self.level);
+        assert!(ct.poly_type == PolyType::Q);
+
+        let level = self.level;
+        let ctx = self.ksk_key.decomposition_ctx(params, level);
+        let q_ctx = params.poly_ctx(&PolyType::Q, level);
+
+        let parts = c1_parts
+            .iter()
+            .map(|p| ctx.substitute(p, &self.substitution))
+            .collect_vec();
+        let (d0, d1) = self.ksk_key.switch_decomposed_qp(params, &parts, level);
+
+        QpCiphertext {
+            c: vec![
+                q_ctx.substitute(&ct.c[0], &self.substitution),
+                q_ctx.zero(ct.c[0].representation.clone()),
+            ],
+            d: vec![d0, d1],
+            level,
+            method: self.ksk_key.method(),
+            encoding_type: ct.encoding_type.clone(),
+            noise: ct
+                .noise
+                .and_then(|n| n.key_switch(&self.ksk_key, 1, params, level)),
+        }
+    }
+
+    /// Adds substituted c0 to key switched c1 `(cs0, cs1)`
+    fn finish_rotation(
+        &self,
+        ct: &Ciphertext,
+        mut cs0: Poly,
+        mut cs1: Poly,
+        params: &BfvParameters,
+    ) -> Ciphertext {
+        let level = self.level;
+        let q_ctx = params.poly_ctx(&PolyType::Q, level);
 
         // Key switch returns polynomial in Evaluation form
         if ct.c[0].representation != cs0.representation {
@@ -85,6 +146,10 @@ impl GaloisKey {
             poly_type: PolyType::Q,
             level,
             seed: None,
+            encoding_type: ct.encoding_type.clone(),
+            noise: ct
+                .noise
+                .and_then(|n| n.key_switch(&self.ksk_key, 1, params, level)),
         }
     }
 }
@@ -1,21 +1,193 @@
 use crate::modulus::Modulus;
 use crate::{mod_inverse_biguint, mod_inverse_biguint_u64};
 use crate::{
-    secret_key::SecretKey, HybridKeySwitchingParameters, Poly, PolyContext, Representation,
+    secret_key::SecretKey, BfvParameters, HybridKeySwitchingParameters, KeySwitchingMethod, Poly,
+    PolyContext, PolyType, Representation,
 };
 use crypto_bigint::rand_core::CryptoRngCore;
 use itertools::{izip, Itertools};
 use ndarray::{azip, s, Array1, Array2, Array3, Axis, IntoNdProducer};
 use num_bigint::{BigUint, ToBigInt};
 use num_traits::{FromPrimitive, One, ToPrimitive};
-use rand::{CryptoRng, SeedableRng};
+use rand::{CryptoRng, RngCore, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 use std::default;
 use traits::Ntt;
-struct BVKeySwitchingKey {
-    c0s: Box<[Poly]>,
-    c1s: Box<[Poly]>,
-    seed: <ChaCha8Rng as SeedableRng>::Seed,
+/// Key switching key for either of the key switching methods
+#[derive(Debug, PartialEq)]
+pub enum KeySwitchingKey {
+    BV(BVKeySwitchingKey),
+    Hybrid(HybridKeySwitchingKey),
+}
+
+impl KeySwitchingKey {
+    /// Generates key switching key for `poly` (in `Evaluation` representation) at `level` using key
+    /// switching method of parameters
+    pub fn new<R: CryptoRng + CryptoRngCore>(
+        params: &BfvParameters,
+        poly: &Poly,
+        sk: &SecretKey,
+        level: usize,
+        rng: &mut R,
+    ) -> KeySwitchingKey {
+        match params.key_switching_method() {
+            KeySwitchingMethod::BV => {
+                let ksk_ctx = params.poly_ctx(&PolyType::Q, level);
+                KeySwitchingKey::BV(BVKeySwitchingKey::new(
+                    poly,
+                    sk,
+                    &ksk_ctx,
+                    params.variance,
+                    rng,
+                ))
+            }
+            KeySwitchingMethod::Hybrid => {
+                let qp_ctx = params.poly_ctx(&PolyType::QP, level);
+                KeySwitchingKey::Hybrid(HybridKeySwitchingKey::new(
+                    params.hybrid_key_switching_params_at_level(level),
+                    poly,
+                    sk,
+                    &qp_ctx,
+                    params.variance,
+                    rng,
+                ))
+            }
+        }
+    }
+
+    /// Returns key switching method of the key
+    pub fn method(&self) -> KeySwitchingMethod {
+        match self {
+            KeySwitchingKey::BV(_) => KeySwitchingMethod::BV,
+            KeySwitchingKey::Hybrid(_) => KeySwitchingMethod::Hybrid,
+        }
+    }
+
+    /// Key switches `poly` (in `Coefficient` representation) at `level`. Returns polynomials in
+    /// `Evaluation` representation.
+    pub fn switch(&self, params: &BfvParameters, poly: &Poly, level: usize) -> (Poly, Poly) {
+        let parts = self.decompose(params, poly, level);
+        self.switch_decomposed(params, &parts, level)
+    }
+
+    /// Decomposes `poly` (in `Coefficient` representation) at `level` using key switching method of the
+    /// key. Returns parts in `Evaluation` representation in context returned by `decomposition_ctx`.
+    ///
+    /// Decomposition only depends on key switching method (see `decompose_with_method`). Thus it can be
+    /// computed once and reused to key switch with multiple keys of the same method (ie hoisting). Since
+    /// substitution commutes with decomposition up to small norm, substituted parts are a valid
+    /// decomposition of substituted `poly`.
+    pub fn decompose(&self, params: &BfvParameters, poly: &Poly, level: usize) -> Vec<Poly> {
+        KeySwitchingKey::decompose_with_method(params, self.method(), poly, level)
+    }
+
+    /// Same as `decompose` for keys of key switching `method`
+    pub fn decompose_with_method(
+        params: &BfvParameters,
+        method: KeySwitchingMethod,
+        poly: &Poly,
+        level: usize,
+    ) -> Vec<Poly> {
+        let ctx = KeySwitchingKey::decomposition_ctx_with_method(params, method, level);
+        match method {
+            KeySwitchingMethod::BV => BVKeySwitchingKey::decompose(poly, &ctx),
+            KeySwitchingMethod::Hybrid => HybridKeySwitchingKey::decompose(
+                params.hybrid_key_switching_params_at_level(level),
+                poly,
+                &ctx,
+            ),
+        }
+    }
+
+    /// Returns context of parts returned by `decompose`, which is Q for BV and QP for hybrid key switching
+    pub fn decomposition_ctx<'a>(
+        &self,
+        params: &'a BfvParameters,
+        level: usize,
+    ) -> PolyContext<'a> {
+        KeySwitchingKey::decomposition_ctx_with_method(params, self.method(), level)
+    }
+
+    /// Same as `decomposition_ctx` for keys of key switching `method`
+    pub fn decomposition_ctx_with_method<'a>(
+        params: &'a BfvParameters,
+        method: KeySwitchingMethod,
+        level: usize,
+    ) -> PolyContext<'a> {
+        match method {
+            KeySwitchingMethod::BV => params.poly_ctx(&PolyType::Q, level),
+            KeySwitchingMethod::Hybrid => params.poly_ctx(&PolyType::QP, level),
+        }
+    }
+
+    /// Key switches polynomial decomposed with `decompose` at `level`. Returns polynomials in
+    /// `Evaluation` representation.
+    pub fn switch_decomposed(
+        &self,
+        params: &BfvParameters,
+        parts: &[Poly],
+        level: usize,
+    ) -> (Poly, Poly) {
+        let (c0, c1) = self.switch_decomposed_qp(params, parts, level);
+        (
+            KeySwitchingKey::mod_down(params, self.method(), c0, level),
+            KeySwitchingKey::mod_down(params, self.method(), c1, level),
+        )
+    }
+
+    /// Same as `switch_decomposed` but returns polynomials in context returned by `decomposition_ctx`,
+    /// ie in QP for hybrid key switching, without switching them to Q.
+    ///
+    /// Returned polynomials (and their sums) must be switched to Q with `mod_down`. Thus sum of many key
+    /// switched polynomials can be accumulated in QP and switched to Q once.
+    pub fn switch_decomposed_qp(
+        &self,
+        params: &BfvParameters,
+        parts: &[Poly],
+        level: usize,
+    ) -> (Poly, Poly) {
+        let ctx = self.decomposition_ctx(params, level);
+        match self {
+            KeySwitchingKey::BV(ksk) => ksk.switch_decomposed(parts, &ctx),
+            KeySwitchingKey::Hybrid(ksk) => ksk.switch_decomposed_qp(parts, &ctx),
+        }
+    }
+
+    /// Same as `switch` but returns polynomials in QP (see `switch_decomposed_qp`)
+    pub fn switch_qp(&self, params: &BfvParameters, poly: &Poly, level: usize) -> (Poly, Poly) {
+        let parts = self.decompose(params, poly, level);
+        self.switch_decomposed_qp(params, &parts, level)
+    }
+
+    /// Switches `poly` (in `Evaluation` representation) returned by `switch_qp` of key with key switching
+    /// `method` (or sum of such polynomials) from QP to Q at `level`, ie returns [poly/P]. Returns `poly` as
+    /// is for BV key switching.
+    pub fn mod_down(
+        params: &BfvParameters,
+        method: KeySwitchingMethod,
+        poly: Poly,
+        level: usize,
+    ) -> Poly {
+        match method {
+            KeySwitchingMethod::BV => poly,
+            KeySwitchingMethod::Hybrid => HybridKeySwitchingKey::mod_down(
+                params.hybrid_key_switching_params_at_level(level),
+                poly,
+                &params.poly_ctx(&PolyType::QP, level),
+                &params.poly_ctx(&PolyType::Q, level),
+                &params.poly_ctx(&PolyType::SpecialP, level),
+            ),
+        }
+    }
+}
+
+/// Key switching key for BV key switching. Polynomial to key switch is decomposed into its RNS
+/// limbs, thus no special moduli are required.
+#[derive(Debug, PartialEq)]
+pub struct BVKeySwitchingKey {
+    pub(crate) c0s: Box<[Poly]>,
+    pub(crate) c1s: Box<[Poly]>,
+    pub(crate) seed: Option<<ChaCha8Rng as SeedableRng>::Seed>,
 }
 
 impl BVKeySwitchingKey {
@@ -23,6 +195,7 @@ impl BVKeySwitchingKey {
         poly: &Poly,
         sk: &SecretKey,
         ksk_ctx: &PolyContext<'_>,
+        variance: usize,
         rng: &mut R,
     ) -> BVKeySwitchingKey {
         // check that ciphertext context has more than on moduli, otherwise key switching does not makes sense
@@ -32,57 +205,64 @@ impl BVKeySwitchingKey {
         let mut seed = <ChaCha8Rng as SeedableRng>::Seed::default();
         rng.fill_bytes(&mut seed);
         let c1s = Self::generate_c1(ksk_ctx, seed);
-        let c0s = Self::generate_c0(ksk_ctx, poly, &c1s, sk, rng);
+        let c0s = Self::generate_c0(ksk_ctx, poly, &c1s, sk, variance, rng);
 
         BVKeySwitchingKey {
             c0s: c0s.into_boxed_slice(),
             c1s: c1s.into_boxed_slice(),
-            seed,
+            seed: Some(seed),
         }
     }
 
     pub fn switch(&self, poly: &Poly, ksk_ctx: &PolyContext<'_>) -> (Poly, Poly) {
+        self.switch_decomposed(&BVKeySwitchingKey::decompose(poly, ksk_ctx), ksk_ctx)
+    }
+
+    /// Decomposes `poly` into its RNS limbs, each lifted to `ksk_ctx`, in `Evaluation` representation
+    pub fn decompose(poly: &Poly, ksk_ctx: &PolyContext<'_>) -> Vec<Poly> {
         // TODO: check that poly matches ksk_ctx
-        // And ksk_ctx matches the key
         debug_assert!(poly.representation == Representation::Coefficient);
 
-        let mut p = ksk_ctx.try_convert_from_u64(
-            poly.coefficients.slice(s![0, ..]).as_slice().unwrap(),
-            Representation::Coefficient,
-        );
-        ksk_ctx.change_representation(&mut p, Representation::Evaluation);
-        let mut c1_out = ksk_ctx.mul(&self.c1s[0], &p);
-        ksk_ctx.mul_assign(&mut p, &self.c0s[0]);
-        let mut c0_out = p;
-
-        izip!(
-            self.c0s.iter(),
-            self.c1s.iter(),
-            poly.coefficients.outer_iter()
-        )
-        .skip(1)
-        .for_each(|(c0, c1, rests)| {
-            let mut p = ksk_ctx
-                .try_convert_from_u64(rests.as_slice().unwrap(), Representation::Coefficient);
-            ksk_ctx.change_representation(&mut p, Representation::Evaluation);
-
-            ksk_ctx.add_assign(&mut c1_out, &ksk_ctx.mul(c1, &p));
-            ksk_ctx.mul_assign(&mut p, &c0);
-            ksk_ctx.add_assign(&mut c0_out, &p);
-        });
+        poly.coefficients
+            .outer_iter()
+            .map(|limb| {
+                let mut p = ksk_ctx
+                    .try_convert_from_u64(limb.as_slice().unwrap(), Representation::Coefficient);
+                ksk_ctx.change_representation(&mut p, Representation::Evaluation);
+                p
+            })
+            .collect_vec()
+    }
+
+    /// Key switches polynomial decomposed with `decompose`
+    pub fn switch_decomposed(&self, parts: &[Poly], ksk_ctx: &PolyContext<'_>) -> (Poly, Poly) {
+        // TODO: check that ksk_ctx matches the key
+        let mut c1_out = ksk_ctx.mul(&self.c1s[0], &parts[0]);
+        let mut c0_out = ksk_ctx.mul(&self.c0s[0], &parts[0]);
+
+        izip!(self.c0s.iter(), self.c1s.iter(), parts.iter())
+            .skip(1)
+            .for_each(|(c0, c1, p)| {
+                ksk_ctx.add_assign(&mut c1_out, &ksk_ctx.mul(c1, p));
+                ksk_ctx.add_assign(&mut c0_out, &ksk_ctx.mul(c0, p));
+            });
 
         (c0_out, c1_out)
     }
 
+    /// Generates one polynomial per modulus in `ksk_ctx` from the seed and returns them in `Evaluation`
+    /// representation
     pub fn generate_c1(
         ksk_ctx: &PolyContext<'_>,
         seed: <ChaCha8Rng as SeedableRng>::Seed,
     ) -> Vec<Poly> {
+        // derive distinct seed for each polynomial
         let mut rng = ChaCha8Rng::from_seed(seed);
         (0..ksk_ctx.moduli_count)
-            .into_iter()
             .map(|_| {
-                let mut p = ksk_ctx.random_with_seed(seed);
+                let mut poly_seed = <ChaCha8Rng as SeedableRng>::Seed::default();
+                rng.fill_bytes(&mut poly_seed);
+                let mut p = ksk_ctx.random_with_seed(poly_seed);
                 ksk_ctx.change_representation(&mut p, Representation::Evaluation);
                 p
             })
@@ -94,6 +274,7 @@ impl BVKeySwitchingKey {
         poly: &Poly,
         c1s: &[Poly],
         sk: &SecretKey,
+        variance: usize,
         rng: &mut R,
     ) -> Vec<Poly> {
         debug_assert!(poly.representation == Representation::Evaluation);
@@ -122,7 +303,7 @@ impl BVKeySwitchingKey {
                 // m = gi*poly
                 ksk_ctx.mul_assign(&mut g, &poly);
 
-                let mut e = ksk_ctx.random_gaussian(Representation::Coefficient, 10, rng);
+                let mut e = ksk_ctx.random_gaussian(Representation::Coefficient, variance, rng);
                 ksk_ctx.change_representation(&mut e, Representation::Evaluation);
                 // m + e
                 ksk_ctx.add_assign(&mut e, &g);
@@ -189,14 +370,23 @@ impl HybridKeySwitchingKey {
         ksk_ctx: &PolyContext<'_>,
         specialp_ctx: &PolyContext<'_>,
     ) -> (Poly, Poly) {
+        let parts = HybridKeySwitchingKey::decompose(ksk_params, poly, qp_ctx);
+        self.switch_decomposed(ksk_params, &parts, qp_ctx, ksk_ctx, specialp_ctx)
+    }
+
+    /// Divides `poly` into `dnum` parts, each with `alpha` moduli, and switches each part from Qj to QP.
+    /// Returns parts in `Evaluation` representation.
+    pub fn decompose(
+        ksk_params: &HybridKeySwitchingParameters,
+        poly: &Poly,
+        qp_ctx: &PolyContext<'_>,
+    ) -> Vec<Poly> {
         // TODO: check poly context
         debug_assert!(poly.representation == Representation::Coefficient);
 
         let alpha = ksk_params.alpha;
 
-        // divide poly into parts and switch them from Qj to QP and key switch
-        let mut c0_out = Poly::placeholder();
-        let mut c1_out = Poly::placeholder();
+        let mut parts = Vec::with_capacity(ksk_params.dnum);
         for i in 0..ksk_params.dnum {
             let mut qp_poly = qp_ctx.zero(Representation::Coefficient);
 
@@ -271,38 +461,59 @@ impl HybridKeySwitchingKey {
             });
 
             qp_ctx.change_representation(&mut qp_poly, Representation::Evaluation);
-
-            if c1_out.representation == Representation::Unknown {
-                c1_out = qp_ctx.mul(&qp_poly, &self.c1s[i]);
-                qp_ctx.mul_assign(&mut qp_poly, &self.c0s[i]);
-                c0_out = qp_poly;
-            } else {
-                qp_ctx.add_assign(&mut c1_out, &qp_ctx.mul(&qp_poly, &self.c1s[i]));
-                qp_ctx.mul_assign(&mut qp_poly, &self.c0s[i]);
-                qp_ctx.add_assign(&mut c0_out, &qp_poly);
-            }
+            parts.push(qp_poly);
         }
+        parts
+    }
+
+    /// Key switches polynomial decomposed with `decompose`. Returns polynomials in Q.
+    pub fn switch_decomposed(
+        &self,
+        ksk_params: &HybridKeySwitchingParameters,
+        parts: &[Poly],
+        qp_ctx: &PolyContext<'_>,
+        ksk_ctx: &PolyContext<'_>,
+        specialp_ctx: &PolyContext<'_>,
+    ) -> (Poly, Poly) {
+        let (c0_out, c1_out) = self.switch_decomposed_qp(parts, qp_ctx);
 
         // switch results from QP to Q
-        let c0_out = qp_ctx.approx_mod_down(
-            c0_out,
-            &ksk_ctx,
-            &specialp_ctx,
-            &ksk_params.p_hat_inv_modp,
-            &ksk_params.p_hat_modq,
-            &ksk_params.p_inv_modq,
-        );
+        (
+            HybridKeySwitchingKey::mod_down(ksk_params, c0_out, qp_ctx, ksk_ctx, specialp_ctx),
+            HybridKeySwitchingKey::mod_down(ksk_params, c1_out, qp_ctx, ksk_ctx, specialp_ctx),
+        )
+    }
+
+    /// Key switches polynomial decomposed with `decompose`. Returns polynomials in QP.
+    pub fn switch_decomposed_qp(&self, parts: &[Poly], qp_ctx: &PolyContext<'_>) -> (Poly, Poly) {
+        let mut c1_out = qp_ctx.mul(&parts[0], &self.c1s[0]);
+        let mut c0_out = qp_ctx.mul(&parts[0], &self.c0s[0]);
+        izip!(self.c0s.iter(), self.c1s.iter(), parts.iter())
+            .skip(1)
+            .for_each(|(c0, c1, p)| {
+                qp_ctx.add_assign(&mut c1_out, &qp_ctx.mul(p, c1));
+                qp_ctx.add_assign(&mut c0_out, &qp_ctx.mul(p, c0));
+            });
+
+        (c0_out, c1_out)
+    }
 
-        let c1_out = qp_ctx.approx_mod_down(
-            c1_out,
-            &ksk_ctx,
-            &specialp_ctx,
+    /// Switches `qp_poly` from QP to Q, ie returns [qp_poly/P]
+    pub fn mod_down(
+        ksk_params: &HybridKeySwitchingParameters,
+        qp_poly: Poly,
+        qp_ctx: &PolyContext<'_>,
+        ksk_ctx: &PolyContext<'_>,
+        specialp_ctx: &PolyContext<'_>,
+    ) -> Poly {
+        qp_ctx.approx_mod_down(
+            qp_poly,
+            ksk_ctx,
+            specialp_ctx,
             &ksk_params.p_hat_inv_modp,
             &ksk_params.p_hat_modq,
             &ksk_params.p_inv_modq,
-        );
-
-        (c0_out, c1_out)
+        )
     }
 
     /// Generates `count` polynomials from the seed and returns them in `Coefficient` representation
@@ -419,7 +630,7 @@ mod tests {
         let sk = SecretKey::random(params.degree, params.hw, &mut rng);
 
         let poly = ksk_ctx.random(Representation::Evaluation, &mut rng);
-        let ksk = BVKeySwitchingKey::new(&poly, &sk, &ksk_ctx, &mut rng);
+        let ksk = BVKeySwitchingKey::new(&poly, &sk, &ksk_ctx, params.variance, &mut rng);
 
         let mut other_poly = ksk_ctx.random(Representation::Coefficient, &mut rng);
 
@@ -484,4 +695,51 @@ mod tests {
             dbg!(&diff_bits);
         });
     }
+    #[test]
+    fn hybrid_key_switching_any_alpha() {
+        let mut rng = thread_rng();
+
+        // (alpha, special moduli sizes)
+        for (alpha, specialp_bits) in [(1, vec![60]), (2, vec![50, 50]), (5, vec![60; 5])] {
+            let mut params = BfvParameters::new(&[50; 5], 65537, 1 << 4);
+            params.enable_hybrid_key_switching_with_alpha(&specialp_bits, alpha);
+            assert_eq!(params.dnum, Some(5_usize.div_ceil(alpha)));
+
+            let ksk_ctx = params.poly_ctx(&PolyType::Q, 0);
+            let specialp_ctx = params.poly_ctx(&PolyType::SpecialP, 0);
+            let qp_ctx = params.poly_ctx(&PolyType::QP, 0);
+            let ksk_params = params.hybrid_key_switching_params_at_level(0);
+
+            let sk = SecretKey::random(params.degree, params.hw, &mut rng);
+            let poly = ksk_ctx.random(Representation::Evaluation, &mut rng);
+            let ksk = HybridKeySwitchingKey::new(
+                ksk_params,
+                &poly,
+                &sk,
+                &qp_ctx,
+                params.variance,
+                &mut rng,
+            );
+
+            let mut other_poly = ksk_ctx.random(Representation::Coefficient, &mut rng);
+            let cs = ksk.switch(ksk_params, &other_poly, &qp_ctx, &ksk_ctx, &specialp_ctx);
+
+            let mut sk_poly =
+                ksk_ctx.try_convert_from_i64_small(&sk.coefficients, Representation::Coefficient);
+            ksk_ctx.change_representation(&mut sk_poly, Representation::Evaluation);
+            let res = ksk_ctx.add(&cs.0, &ksk_ctx.mul(&cs.1, &sk_poly));
+
+            // expected
+            ksk_ctx.change_representation(&mut other_poly, Representation::Evaluation);
+            let expected_poly = ksk_ctx.mul(&other_poly, &poly);
+
+            let mut diff = ksk_ctx.sub(&res, &expected_poly);
+            ksk_ctx.change_representation(&mut diff, Representation::Coefficient);
+
+            ksk_ctx.try_convert_to_biguint(&diff).iter().for_each(|v| {
+                let diff_bits = std::cmp::min(v.bits(), (ksk_ctx.big_q() - v).bits());
+                assert!(diff_bits <= 70);
+            });
+        }
+    }
 }
@@ -1,39 +1,59 @@
 mod ciphertext;
+mod crt_evaluator;
+mod error;
 mod evaluation_key;
 mod evaluator;
 mod galois_key;
 mod key_switching_key;
+mod linear_transform;
 mod modulus;
 mod nb_theory;
+mod noise;
 mod ntt;
 mod parameters;
 mod plaintext;
 mod poly;
+mod public_key;
 mod relinearization_key;
 mod secret_key;
+mod security;
 mod utils;
 
 #[cfg(feature = "serialize")]
 mod proto;
 #[cfg(feature = "serialize")]
 pub use proto::proto::{
-    Ciphertext as CiphertextProto, EvaluationKey as EvaluationKeyProto, SecretKey as SecretKeyProto,
+    Ciphertext as CiphertextProto, EvaluationKey as EvaluationKeyProto,
+    PublicKey as PublicKeyProto, SecretKey as SecretKeyProto,
 };
+#[cfg(feature = "serialize")]
+pub use proto::{ObjectType, FORMAT_VERSION};
+#[cfg(feature = "serde")]
+mod serde_impl;
+#[cfg(feature = "serde")]
+pub use serde_impl::WithParameters;
 
 pub use ciphertext::*;
+pub use crt_evaluator::*;
+pub use error::*;
 pub use evaluation_key::*;
 pub use evaluator::*;
 pub use galois_key::*;
 pub use key_switching_key::*;
+pub use linear_transform::*;
 pub use modulus::*;
 pub use nb_theory::*;
+pub use noise::*;
 pub use ntt::NttOperator;
-pub use parameters::{HybridKeySwitchingParameters, PolyType};
+pub use parameters::{HybridKeySwitchingParameters, KeySwitchingMethod, PolyType};
 pub use plaintext::*;
 pub use poly::{Poly, Representation, Substitution};
+pub use public_key::*;
 pub use relinearization_key::*;
 pub use secret_key::*;
+pub use security::*;
 pub use utils::*;
 
 pub type BfvParameters = parameters::BfvParameters<NttOperator>;
+pub type BfvParametersBuilder = parameters::BfvParametersBuilder<NttOperator>;
 pub type PolyContext<'a> = poly::PolyContext<'a, NttOperator>;
@@ -0,0 +1,328 @@
+use crate::{BfvError, BfvParameters, Encoding, Plaintext, PolyCache, PolyType};
+use itertools::Itertools;
+
+/// Plaintext matrix M of dimension d to multiply with an encrypted vector using the diagonal method.
+///
+/// Vector v of length d must be encrypted with SIMD encoding and replicated with period d along each row
+/// of slots (ie slot i holds v[i mod d]), which requires d to be a power of two <= N/2. Then rotating a row
+/// by k rotates v by k and
+///
+/// M v = Σ_k diag_k ⊙ rot_k(v), where diag_k[i] = M[i][(i + k) mod d]
+///
+/// Product is evaluated with baby-step giant-step (BSGS) algorithm of [Halevi-Shoup](https://eprint.iacr.org/2018/244.pdf).
+/// Writing k = g b + j for b ~ √d baby steps,
+///
+/// M v = Σ_g rot_{gb}(Σ_j rot_{-gb}(diag_{gb+j}) ⊙ rot_j(v))
+///
+/// Thus diagonals are pre-rotated by -gb and encoded with `PolyCache::Mul`, baby-step rotations rot_j(v) are
+/// hoisted (c_1 of v is decomposed once), and only b - 1 + d/b rotation keys are required (see `rotations`).
+/// Output vector is replicated with period d as well.
+#[derive(Clone)]
+pub struct LinearTransform {
+    dim: usize,
+    level: usize,
+    baby_steps: usize,
+    // (k, rot_{-gb}(diag_k)) for non-zero diagonals, sorted by k
+    diagonals: Vec<(usize, Plaintext)>,
+}
+
+impl LinearTransform {
+    pub fn new(params: &BfvParameters, matrix: &[Vec<u64>], level: usize) -> LinearTransform {
+        LinearTransform::try_new(params, matrix, level).unwrap()
+    }
+
+    /// Creates linear transform for square `matrix` (given as rows) with values in [0, t) to be applied to
+    /// ciphertexts at `level`. All-zero diagonals are skipped.
+    ///
+    /// Returns error if matrix is not square or its dimension is not a power of two <= N/2.
+    pub fn try_new(
+        params: &BfvParameters,
+        matrix: &[Vec<u64>],
+        level: usize,
+    ) -> Result<LinearTransform, BfvError> {
+        let dim = matrix.len();
+        if let Some(row) = matrix.iter().find(|row| row.len() != dim) {
+            return Err(BfvError::InvalidMatrixShape {
+                rows: dim,
+                cols: row.len(),
+                degree: params.degree,
+            });
+        }
+
+        let diagonals = (0..dim)
+            .map(|k| (k, (0..dim).map(|i| matrix[i][(i + k) % dim]).collect_vec()))
+            .collect_vec();
+        LinearTransform::try_from_diagonals(params, dim, &diagonals, level)
+    }
+
+    pub fn from_diagonals(
+        params: &BfvParameters,
+        dim: usize,
+        diagonals: &[(usize, Vec<u64>)],
+        level: usize,
+    ) -> LinearTransform {
+        LinearTransform::try_from_diagonals(params, dim, diagonals, level).unwrap()
+    }
+
+    /// Creates linear transform of dimension `dim` from its diagonals `(k, diag_k)`, where
+    /// diag_k[i] = M[i][(i + k) mod dim]. Missing (and all-zero) diagonals are treated as zero.
+    ///
+    /// Returns error if `dim` is not a power of two <= N/2, or any diagonal has index >= `dim` or length
+    /// other than `dim`.
+    pub fn try_from_diagonals(
+        params: &BfvParameters,
+        dim: usize,
+        diagonals: &[(usize, Vec<u64>)],
+        level: usize,
+    ) -> Result<LinearTransform, BfvError> {
+        if !dim.is_power_of_two() || dim > params.degree / 2 {
+            return Err(BfvError::InvalidMatrixShape {
+                rows: dim,
+                cols: dim,
+                degree: params.degree,
+            });
+        }
+        if let Some((index, diagonal)) = diagonals
+            .iter()
+            .find(|(index, diagonal)| *index >= dim || diagonal.len() != dim)
+        {
+            return Err(BfvError::InvalidDiagonal {
+                index: *index,
+                len: diagonal.len(),
+                dim,
+            });
+        }
+
+        // b = 2^ceil(log(d)/2) baby steps and d/b giant steps
+        let baby_steps = 1 << ((dim.trailing_zeros() + 1) / 2);
+
+        let encoding = Encoding::simd(level, PolyCache::Mul(PolyType::Q));
+        let diagonals = diagonals
+            .iter()
+            .filter(|(_, diagonal)| diagonal.iter().any(|v| *v != 0))
+            .sorted_by_key(|(k, _)| *k)
+            .map(|(k, diagonal)| {
+                // rotate right by gb and replicate with period d across all slots
+                let giant = k - k % baby_steps;
+                let m = (0..params.degree)
+                    .map(|i| diagonal[(i + dim - giant) % dim])
+                    .collect_vec();
+                Ok((*k, Plaintext::try_encode(&m, params, encoding.clone())?))
+            })
+            .collect::<Result<Vec<_>, BfvError>>()?;
+
+        Ok(LinearTransform {
+            dim,
+            level,
+            baby_steps,
+            diagonals,
+        })
+    }
+
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    /// Returns rotations for which galois keys are required to evaluate the transform, ie baby steps j
+    /// and giant steps gb of non-zero diagonals (except 0). Check `EvaluationKey::new_with_linear_transform_rotations`.
+    pub fn rotations(&self) -> Vec<isize> {
+        self.baby_rotations()
+            .into_iter()
+            .chain(self.giant_rotations())
+            .filter(|r| *r != 0)
+            .map(|r| r as isize)
+            .sorted()
+            .dedup()
+            .collect_vec()
+    }
+
+    /// Returns baby steps j of non-zero diagonals, including 0
+    pub(crate) fn baby_rotations(&self) -> Vec<usize> {
+        self.diagonals
+            .iter()
+            .map(|(k, _)| k % self.baby_steps)
+            .sorted()
+            .dedup()
+            .collect_vec()
+    }
+
+    /// Returns giant steps gb of non-zero diagonals, including 0
+    fn giant_rotations(&self) -> Vec<usize> {
+        self.diagonals
+            .iter()
+            .map(|(k, _)| k - k % self.baby_steps)
+            .dedup()
+            .collect_vec()
+    }
+
+    /// Returns pre-rotated diagonals grouped by giant step as `(gb, [(j, diagonal)])`
+    pub(crate) fn giant_groups(&self) -> Vec<(usize, Vec<(usize, &Plaintext)>)> {
+        self.diagonals
+            .iter()
+            .group_by(|(k, _)| k - k % self.baby_steps)
+            .into_iter()
+            .map(|(giant, group)| (giant, group.map(|(k, pt)| (k - giant, pt)).collect_vec()))
+            .collect_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Encoding, EvaluationKey, Evaluator, KeySwitchingMethod, SecretKey};
+    use rand::{thread_rng, Rng};
+
+    /// Returns M v mod t
+    fn mat_vec(matrix: &[Vec<u64>], v: &[u64], t: u64) -> Vec<u64> {
+        matrix
+            .iter()
+            .map(|row| {
+                row.iter().zip(v.iter()).fold(0u128, |acc, (a, b)| {
+                    (acc + (*a as u128) * (*b as u128)) % t as u128
+                }) as u64
+            })
+            .collect_vec()
+    }
+
+    #[test]
+    fn linear_transform_works() {
+        let mut rng = thread_rng();
+        let params = BfvParameters::new(&[50; 4], 65537, 1 << 4);
+        let sk = SecretKey::random(params.degree, params.hw, &mut rng);
+        let t = params.plaintext_modulus;
+        let degree = params.degree;
+
+        let mut hybrid_params = params.clone();
+        hybrid_params.enable_hybrid_key_switching(&[50, 50, 50]);
+        assert_eq!(
+            hybrid_params.key_switching_method(),
+            KeySwitchingMethod::Hybrid
+        );
+
+        for params in [params, hybrid_params] {
+            let evaluator = Evaluator::new(params);
+            for dim in [1, 2, 4, 8] {
+                let matrix = (0..dim)
+                    .map(|_| (0..dim).map(|_| rng.gen_range(0..t)).collect_vec())
+                    .collect_vec();
+                let lt = LinearTransform::new(evaluator.params(), &matrix, 0);
+                let ek = EvaluationKey::new_with_linear_transform_rotations(
+                    evaluator.params(),
+                    &sk,
+                    &[],
+                    &lt,
+                    &mut rng,
+                );
+                assert_eq!(ek.rtgs.len(), lt.rotations().len());
+
+                // replicate vector with period dim
+                let v = (0..dim).map(|_| rng.gen_range(0..t)).collect_vec();
+                let m = (0..degree).map(|i| v[i % dim]).collect_vec();
+                let ct = evaluator.encrypt(
+                    &sk,
+                    &evaluator.plaintext_encode(&m, Encoding::default()),
+                    &mut rng,
+                );
+
+                let ct_res = evaluator.linear_transform(&ct, &lt, &ek);
+                assert!(evaluator.noise_budget(&sk, &ct_res) > 0);
+                let res = evaluator
+                    .plaintext_decode(&evaluator.decrypt(&sk, &ct_res), Encoding::default());
+
+                let expected = mat_vec(&matrix, &v, t);
+                let expected = (0..degree).map(|i| expected[i % dim]).collect_vec();
+                assert_eq!(res, expected, "dim {dim}");
+            }
+        }
+    }
+
+    #[test]
+    fn linear_transform_from_diagonals() {
+        let mut rng = thread_rng();
+        let params = BfvParameters::default(3, 1 << 4);
+        let sk = SecretKey::random(params.degree, params.hw, &mut rng);
+        let t = params.plaintext_modulus;
+        let dim = params.degree / 2;
+
+        // tridiagonal matrix
+        let diagonals = [0, 1, dim - 1]
+            .iter()
+            .map(|k| (*k, (0..dim).map(|_| rng.gen_range(1..t)).collect_vec()))
+            .collect_vec();
+        let mut matrix = vec![vec![0u64; dim]; dim];
+        diagonals.iter().for_each(|(k, diagonal)| {
+            (0..dim).for_each(|i| matrix[i][(i + k) % dim] = diagonal[i]);
+        });
+
+        let evaluator = Evaluator::new(params);
+        let lt = LinearTransform::from_diagonals(evaluator.params(), dim, &diagonals, 0);
+        // 4 baby steps: 1 and 3, and giant step 4
+        assert_eq!(lt.rotations(), vec![1, 3, 4]);
+        assert_eq!(
+            lt.rotations(),
+            LinearTransform::new(evaluator.params(), &matrix, 0).rotations()
+        );
+
+        let ek = EvaluationKey::new_with_linear_transform_rotations(
+            evaluator.params(),
+            &sk,
+            &[],
+            &lt,
+            &mut rng,
+        );
+        let v = (0..dim).map(|_| rng.gen_range(0..t)).collect_vec();
+        let m = (0..evaluator.params().degree)
+            .map(|i| v[i % dim])
+            .collect_vec();
+        let ct = evaluator.encrypt(
+            &sk,
+            &evaluator.plaintext_encode(&m, Encoding::default()),
+            &mut rng,
+        );
+        let res = evaluator.plaintext_decode(
+            &evaluator.decrypt(&sk, &evaluator.linear_transform(&ct, &lt, &ek)),
+            Encoding::default(),
+        );
+        assert_eq!(res[..dim], mat_vec(&matrix, &v, t));
+
+        // missing galois key
+        let ek = EvaluationKey::new(evaluator.params(), &sk, &[], &[0], &[1], &mut rng);
+        assert_eq!(
+            evaluator.try_linear_transform(&ct, &lt, &ek),
+            Err(BfvError::MissingGaloisKey {
+                rotate_by: 3,
+                level: 0
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_linear_transforms() {
+        let params = BfvParameters::default(3, 1 << 4);
+
+        assert!(matches!(
+            LinearTransform::try_new(&params, &[vec![1, 2], vec![3]], 0),
+            Err(BfvError::InvalidMatrixShape {
+                rows: 2,
+                cols: 1,
+                ..
+            })
+        ));
+        assert!(matches!(
+            LinearTransform::try_from_diagonals(&params, 16, &[], 0),
+            Err(BfvError::InvalidMatrixShape { rows: 16, .. })
+        ));
+        assert!(matches!(
+            LinearTransform::try_from_diagonals(&params, 4, &[(4, vec![1; 4])], 0),
+            Err(BfvError::InvalidDiagonal {
+                index: 4,
+                len: 4,
+                dim: 4
+            })
+        ));
+    }
+}
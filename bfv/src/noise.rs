@@ -0,0 +1,222 @@
+use crate::{BfvParameters, KeySwitchingKey};
+use itertools::Itertools;
+
+/// Heuristic upper bound on noise of a ciphertext, stored as bits (ie log2 of the bound).
+///
+/// Ciphertext (c_0, c_1, ..., c_k) at level l decrypts as [c_0 + c_1 s + ... + c_k s^k]_Ql = [Ql m / t] + v.
+/// Estimate bounds ||v|| (infinity norm) using expansion factor δ = 2√N for products of polynomials (see
+/// `BfvParameters::v_norm`), thus it does not require the secret key. Decryption succeeds as long as
+/// ||v|| < Ql / 2t.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NoiseEstimate {
+    bits: f64,
+}
+
+impl NoiseEstimate {
+    fn from_bound(bound: f64) -> NoiseEstimate {
+        NoiseEstimate {
+            bits: bound.max(1.0).log2(),
+        }
+    }
+
+    /// Returns log2 of estimated bound on noise
+    pub fn bits(&self) -> f64 {
+        self.bits
+    }
+
+    /// Returns estimated noise budget in bits at `level`, ie log2(Ql / 2t) - log2(||v||). Decryption is
+    /// expected to fail once budget is <= 0.
+    pub fn budget(&self, params: &BfvParameters, level: usize) -> f64 {
+        log2_ql(params, level) - (params.plaintext_modulus as f64).log2() - 1.0 - self.bits
+    }
+
+    /// Noise of ciphertext encrypted with secret key, ie e
+    pub(crate) fn fresh(params: &BfvParameters) -> NoiseEstimate {
+        NoiseEstimate::from_bound(error_bound(params))
+    }
+
+    /// Noise of ciphertext encrypted with public key, ie e_0 u + e_1 + e_2 s
+    pub(crate) fn fresh_public(params: &BfvParameters) -> NoiseEstimate {
+        let delta = expansion_factor(params);
+        NoiseEstimate::from_bound(error_bound(params) * (1.0 + 2.0 * delta))
+    }
+
+    /// Noise of sum (or difference) of ciphertexts. Scaled messages may differ from scaled sum by 1.
+    pub(crate) fn add(&self, other: &NoiseEstimate) -> NoiseEstimate {
+        NoiseEstimate {
+            bits: log2_sum(&[self.bits, other.bits, 0.0]),
+        }
+    }
+
+    /// Noise after adding (or subtracting) a plaintext or scalar
+    pub(crate) fn add_plaintext(&self) -> NoiseEstimate {
+        NoiseEstimate {
+            bits: log2_sum(&[self.bits, 0.0]),
+        }
+    }
+
+    /// Noise after adding noise of `bit_size` bits
+    pub(crate) fn add_bits(&self, bit_size: usize) -> NoiseEstimate {
+        NoiseEstimate {
+            bits: log2_sum(&[self.bits, bit_size as f64]),
+        }
+    }
+
+    /// Noise after multiplying by scalar with absolute (centered) value `scalar`. Scaling rounding error of
+    /// message (<= 1/2) is multiplied by scalar as well.
+    pub(crate) fn mul_scalar(&self, scalar: u64) -> NoiseEstimate {
+        if scalar == 0 {
+            return NoiseEstimate { bits: 0.0 };
+        }
+        NoiseEstimate {
+            bits: log2_sum(&[self.bits, -1.0]) + (scalar as f64).log2(),
+        }
+    }
+
+    /// Noise after multiplying by plaintext polynomial with coefficients in [0, t)
+    pub(crate) fn mul_plaintext(&self, params: &BfvParameters) -> NoiseEstimate {
+        let t = params.plaintext_modulus as f64;
+        NoiseEstimate {
+            bits: log2_sum(&[self.bits, -1.0]) + (expansion_factor(params) * t).log2(),
+        }
+    }
+
+    /// Noise of product of ciphertexts `lhs` and `rhs` with `lhs_size` and `rhs_size` polynomials at `level`
+    /// after scaling by t/Ql.
+    ///
+    /// Writing c_i(s) = [Ql m_i / t] + v_i + Ql k_i, where ||k_i|| <= Σ_{j<size_i} δ^j, noise of product is
+    /// δt(v_1 + v_2) + δt(k_1 v_2 + k_2 v_1) + δt v_1 v_2 / Ql plus error of rounding the product.
+    pub(crate) fn mul(
+        lhs: &NoiseEstimate,
+        lhs_size: usize,
+        rhs: &NoiseEstimate,
+        rhs_size: usize,
+        params: &BfvParameters,
+        level: usize,
+    ) -> NoiseEstimate {
+        let delta = expansion_factor(params);
+        let log_delta_t = (delta * params.plaintext_modulus as f64).log2();
+
+        // include rounding error of scaled messages
+        let v1 = log2_sum(&[lhs.bits, -1.0]);
+        let v2 = log2_sum(&[rhs.bits, -1.0]);
+        let k1 = log2_rounding(delta, lhs_size);
+        let k2 = log2_rounding(delta, rhs_size);
+
+        NoiseEstimate {
+            bits: log2_sum(&[
+                log_delta_t + log2_sum(&[v1, v2]),
+                log_delta_t + log2_sum(&[k1 + v2, k2 + v1]),
+                log_delta_t + v1 + v2 - log2_ql(params, level),
+                log2_rounding(delta, lhs_size + rhs_size - 1) - 1.0,
+            ]),
+        }
+    }
+
+    /// Noise after key switching `count` polynomials with `ksk` at `level`. Returns None if noise of `ksk`
+    /// cannot be estimated (see `key_switching_bits`).
+    pub(crate) fn key_switch(
+        &self,
+        ksk: &KeySwitchingKey,
+        count: usize,
+        params: &BfvParameters,
+        level: usize,
+    ) -> Option<NoiseEstimate> {
+        let ks_bits = key_switching_bits(ksk, params, level)? + (count as f64).log2();
+        Some(NoiseEstimate {
+            bits: log2_sum(&[self.bits, ks_bits]),
+        })
+    }
+
+    /// Noise of ciphertext with `size` polynomials after switching from `level` to `level + 1`. Noise is
+    /// scaled down by last modulus and rounding error of each polynomial is multiplied by s^j.
+    pub(crate) fn mod_down_next(
+        &self,
+        size: usize,
+        params: &BfvParameters,
+        level: usize,
+    ) -> NoiseEstimate {
+        let q_last = params.ciphertext_moduli[params.ciphertext_moduli.len() - 1 - level] as f64;
+        NoiseEstimate {
+            bits: log2_sum(&[
+                self.bits - q_last.log2(),
+                log2_rounding(expansion_factor(params), size),
+                0.0,
+            ]),
+        }
+    }
+}
+
+/// Returns estimated noise bits added by key switching a single polynomial with `ksk` at `level`
+///
+/// For BV key switching noise is Σ_i [c]_qi e_i (see `BfvParameters::noise_ks`). For hybrid key switching
+/// noise is Σ_j [c]_Qj e_j / P plus error of rounding by P, where Qj is product of alpha moduli. Alpha is
+/// derived from no. of digits (ie dnum) of the key.
+///
+/// Returns None for hybrid key without digits or if parameters do not have special moduli.
+fn key_switching_bits(ksk: &KeySwitchingKey, params: &BfvParameters, level: usize) -> Option<f64> {
+    let delta = expansion_factor(params);
+    let q_moduli = &params.ciphertext_moduli[..params.ciphertext_moduli.len() - level];
+    let log_error = (delta * error_bound(params) / 2.0).log2();
+
+    match ksk {
+        KeySwitchingKey::BV(_) => {
+            let max_qi = q_moduli
+                .iter()
+                .map(|qi| (*qi as f64).log2())
+                .fold(0.0, f64::max);
+            Some(log_error + max_qi + (q_moduli.len() as f64).log2())
+        }
+        KeySwitchingKey::Hybrid(ksk) => {
+            let dnum = ksk.c0s.len();
+            if dnum == 0 {
+                return None;
+            }
+            let alpha = q_moduli.len().div_ceil(dnum);
+            let max_qj = q_moduli
+                .chunks(alpha)
+                .map(|qj| qj.iter().map(|qi| (*qi as f64).log2()).sum::<f64>())
+                .fold(0.0, f64::max);
+            let log_p = params
+                .special_moduli
+                .as_ref()?
+                .iter()
+                .map(|pj| (*pj as f64).log2())
+                .sum::<f64>();
+            Some(log2_sum(&[
+                log_error + max_qj - log_p + (dnum as f64).log2(),
+                ((1.0 + delta) / 2.0).log2(),
+            ]))
+        }
+    }
+}
+
+/// Returns expansion factor δ = 2√N
+fn expansion_factor(params: &BfvParameters) -> f64 {
+    2.0 * (params.degree as f64).sqrt()
+}
+
+/// Returns bound on error sampled from centered binomial distribution, which is 2 * variance
+fn error_bound(params: &BfvParameters) -> f64 {
+    2.0 * params.variance as f64
+}
+
+fn log2_ql(params: &BfvParameters, level: usize) -> f64 {
+    params.ciphertext_moduli[..params.ciphertext_moduli.len() - level]
+        .iter()
+        .map(|qi| (*qi as f64).log2())
+        .sum()
+}
+
+/// Returns log2 of Σ_{j<size} δ^j, ie bound on rounding error of ciphertext with `size` polynomials
+/// multiplied by powers of ternary secret
+fn log2_rounding(delta: f64, size: usize) -> f64 {
+    log2_sum(&(0..size).map(|j| j as f64 * delta.log2()).collect_vec())
+}
+
+/// Returns log2(Σ 2^x) for x in `values`
+fn log2_sum(values: &[f64]) -> f64 {
+    let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    max + values.iter().map(|v| (v - max).exp2()).sum::<f64>().log2()
+}
@@ -1,15 +1,20 @@
 use crate::modulus::Modulus;
-use crate::nb_theory::generate_primes_vec;
+use crate::nb_theory::{gcd, generate_primes_vec};
+use crate::security::max_log_qp;
 use crate::{mod_inverse_biguint, mod_inverse_biguint_u64};
 use crate::{poly::poly_context::PolyContext, Poly, Representation};
+use crate::{BfvError, SecretDistribution, SecurityLevel};
 use itertools::Itertools;
 use ndarray::Array2;
 use num_bigint::BigUint;
+use num_bigint_dig::{prime::probably_prime, BigUint as BigUintDig};
 use num_traits::{One, Pow, ToPrimitive};
+use std::marker::PhantomData;
 use std::vec;
 use traits::Ntt;
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PolyType {
     Q,
     P,
@@ -18,6 +23,15 @@ pub enum PolyType {
     QP,
 }
 
+/// Key switching method used by relinearization and galois keys
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub enum KeySwitchingMethod {
+    /// Decomposes polynomial into RNS limbs. Does not require special moduli.
+    BV,
+    /// Decomposes polynomial into dnum parts of alpha moduli each and key switches in QP.
+    Hybrid,
+}
+
 #[derive(PartialEq, Clone, Debug)]
 pub struct BfvParameters<T: Ntt> {
     pub ciphertext_moduli: Vec<u64>,
@@ -34,7 +48,8 @@ pub struct BfvParameters<T: Ntt> {
 
     pub plaintext_modulus: u64,
     pub plaintext_modulus_op: Modulus,
-    pub plaintext_ntt_op: T,
+    /// Only exists if plaintext modulus supports batching (ie is prime and 1 mod 2N)
+    pub plaintext_ntt_op: Option<T>,
     pub degree: usize,
 
     // Convert Utils
@@ -85,18 +100,25 @@ pub struct BfvParameters<T: Ntt> {
     pub ql_inv: Vec<Vec<f64>>,
     pub alphal_modpl: Vec<Array2<u64>>,
 
+    // Key switching method. Set to `Hybrid` once hybrid key switching is enabled
+    key_switching_method: KeySwitchingMethod,
+
     // Hybrid key switching
     pub special_moduli: Option<Vec<u64>>,
     pub special_moduli_ops: Option<Vec<Modulus>>,
     pub special_moduli_ntt_ops: Option<Vec<T>>,
     pub dnum: Option<usize>,
-    pub alpha: Option<usize>, // fixed to 3
+    pub alpha: Option<usize>,
 
     // Hybrid key switching key parameters
     pub hybrid_ksk_parameters: Option<Vec<HybridKeySwitchingParameters>>,
 
     // Mod Down //
     pub lastq_inv_modql: Vec<Vec<u64>>,
+
+    /// SHA-256 digest of serialized parameters. Updated whenever parameters change.
+    #[cfg(feature = "serialize")]
+    pub(crate) fingerprint: Vec<u8>,
 }
 
 impl<T> BfvParameters<T>
@@ -149,6 +171,147 @@ where
         let extension_moduli =
             generate_primes_vec(ciphertext_moduli_sizes, degree, &ciphertext_moduli);
 
+        BfvParameters::new_from_moduli(
+            ciphertext_moduli,
+            extension_moduli,
+            plaintext_modulus,
+            degree,
+        )
+    }
+
+    /// Creates new bfv parameters with given moduli. Special moduli enable hybrid key switching and are
+    /// ignored if empty.
+    ///
+    /// Panics if moduli are invalid. Check `try_with_moduli` for requirements.
+    pub fn with_moduli(
+        ciphertext_moduli: &[u64],
+        extension_moduli: &[u64],
+        special_moduli: &[u64],
+        plaintext_modulus: u64,
+        degree: usize,
+    ) -> BfvParameters<T> {
+        BfvParameters::try_with_moduli(
+            ciphertext_moduli,
+            extension_moduli,
+            special_moduli,
+            plaintext_modulus,
+            degree,
+        )
+        .unwrap()
+    }
+
+    /// Creates new bfv parameters with given moduli. Special moduli enable hybrid key switching and are
+    /// ignored if empty.
+    ///
+    /// Returns error if
+    /// 0. degree is not a power of two >= 16
+    /// 1. any of ciphertext, extension, or special moduli is not NTT friendly (ie p != 1 mod 2N)
+    /// 2. moduli in PQ or QP basis are not pairwise coprime, or plaintext modulus is not coprime to Q
+    /// 3. extension basis is not large enough for HPS multiplication. Extension basis must have as many
+    ///    moduli as ciphertext basis and at every level P must have as many bits as Q.
+    ///
+    /// Alpha for hybrid key switching is set to no. of special moduli. Use
+    /// `try_enable_hybrid_key_switching_with_moduli` to set a different alpha.
+    pub fn try_with_moduli(
+        ciphertext_moduli: &[u64],
+        extension_moduli: &[u64],
+        special_moduli: &[u64],
+        plaintext_modulus: u64,
+        degree: usize,
+    ) -> Result<BfvParameters<T>, BfvError> {
+        check_degree(degree)?;
+        check_ntt_friendly(ciphertext_moduli, degree)?;
+        check_ntt_friendly(extension_moduli, degree)?;
+        check_ntt_friendly(special_moduli, degree)?;
+
+        // pairwise coprime within bases PQ and QP. Extension and special moduli are never part of the same
+        // basis, thus may overlap.
+        check_coprime(ciphertext_moduli, extension_moduli)?;
+        check_coprime(ciphertext_moduli, special_moduli)?;
+        if let Some(qi) = ciphertext_moduli
+            .iter()
+            .find(|qi| gcd(**qi, plaintext_modulus) != 1)
+        {
+            return Err(BfvError::ModuliNotCoprime {
+                a: *qi,
+                b: plaintext_modulus,
+            });
+        }
+
+        // Extension basis
+        if extension_moduli.len() != ciphertext_moduli.len() {
+            return Err(BfvError::ModuliCountMismatch {
+                expected: ciphertext_moduli.len(),
+                found: extension_moduli.len(),
+            });
+        }
+        let bits = |m: &u64| (64 - m.leading_zeros()) as usize;
+        for level in 0..ciphertext_moduli.len() {
+            let level_index = ciphertext_moduli.len() - level;
+            let q_bits = ciphertext_moduli[..level_index]
+                .iter()
+                .map(bits)
+                .sum::<usize>();
+            let p_bits = extension_moduli[..level_index]
+                .iter()
+                .map(bits)
+                .sum::<usize>();
+            if p_bits < q_bits {
+                return Err(BfvError::ExtensionBasisTooSmall { level });
+            }
+        }
+
+        let mut params = BfvParameters::new_from_moduli(
+            ciphertext_moduli.to_vec(),
+            extension_moduli.to_vec(),
+            plaintext_modulus,
+            degree,
+        );
+        if !special_moduli.is_empty() {
+            params.enable_hybrid_key_switching_with_moduli(
+                special_moduli.to_vec(),
+                special_moduli.len(),
+            );
+        }
+        Ok(params)
+    }
+
+    /// Returns parameters with same ciphertext, extension, and special moduli (and same variance, hamming weight,
+    /// alpha, and key switching method) but different plaintext modulus.
+    ///
+    /// Returns error if plaintext modulus is not coprime to Q.
+    pub fn try_with_plaintext_modulus(
+        &self,
+        plaintext_modulus: u64,
+    ) -> Result<BfvParameters<T>, BfvError> {
+        let mut params = BfvParameters::try_with_moduli(
+            &self.ciphertext_moduli,
+            &self.extension_moduli,
+            &[],
+            plaintext_modulus,
+            self.degree,
+        )?;
+        params.change_variance(self.variance);
+        params.change_hamming_weight(self.hw);
+        if let (Some(special_moduli), Some(alpha)) = (self.special_moduli.as_ref(), self.alpha) {
+            params.try_enable_hybrid_key_switching_with_moduli(special_moduli, alpha)?;
+        }
+        params.try_set_key_switching_method(self.key_switching_method)?;
+        Ok(params)
+    }
+
+    /// Precomputes parameters for given moduli without any checks
+    fn new_from_moduli(
+        ciphertext_moduli: Vec<u64>,
+        extension_moduli: Vec<u64>,
+        plaintext_modulus: u64,
+        degree: usize,
+    ) -> BfvParameters<T> {
+        let ciphertext_moduli_sizes = ciphertext_moduli
+            .iter()
+            .map(|qi| (64 - qi.leading_zeros()) as usize)
+            .collect_vec();
+
         // moduli ops
         let ciphertext_moduli_ops = ciphertext_moduli
             .iter()
@@ -513,19 +676,25 @@ where
         }
 
         let plaintext_modulus_op = Modulus::new(plaintext_modulus);
-        let plaintext_ntt_op = T::new(degree, plaintext_modulus);
+        let plaintext_ntt_op = {
+            if supports_batching(plaintext_modulus, degree) {
+                Some(T::new(degree, plaintext_modulus))
+            } else {
+                None
+            }
+        };
 
         // Default to Hamming weight set to N/2.
         let hw = degree / 2;
 
-        BfvParameters {
+        let mut params = BfvParameters {
             ciphertext_moduli,
             extension_moduli,
             ciphertext_moduli_ops,
             extension_moduli_ops,
             ciphertext_ntt_ops,
             extension_ntt_ops,
-            ciphertext_moduli_sizes: ciphertext_moduli_sizes.to_vec(),
+            ciphertext_moduli_sizes,
             max_level: q_size - 1,
             q_size,
             p_size,
@@ -578,6 +747,8 @@ where
             ql_inv,
             alphal_modpl,
 
+            key_switching_method: KeySwitchingMethod::BV,
+
             // Hybrid key switching //
             special_moduli: None,
             alpha: None,
@@ -589,18 +760,107 @@ where
 
             // Mod down next //
             lastq_inv_modql,
-        }
+
+            #[cfg(feature = "serialize")]
+            fingerprint: vec![],
+        };
+        params.update_fingerprint();
+        params
+    }
+
+    /// Returns true if plaintext modulus supports SIMD encoding
+    pub fn supports_batching(&self) -> bool {
+        self.plaintext_ntt_op.is_some()
     }
 
     pub fn change_hamming_weight(&mut self, hw: usize) {
         self.hw = hw;
+        self.update_fingerprint();
+    }
+
+    pub fn change_variance(&mut self, variance: usize) {
+        self.variance = variance;
+        self.update_fingerprint();
+    }
+
+    /// Returns key switching method of relinearization and galois keys generated with parameters
+    pub fn key_switching_method(&self) -> KeySwitchingMethod {
+        self.key_switching_method
     }
 
-    pub fn enable_hybrid_key_switching(&mut self, specialp_bits: &[usize; 3]) {
-        const ALPHA: usize = 3;
-        let dnum = (self.ciphertext_moduli.len() as f64 / ALPHA as f64).ceil() as usize;
+    /// Changes key switching method of relinearization and galois keys generated with parameters.
+    /// Special moduli are kept when switching to `BV` so that `Hybrid` can be selected again.
+    ///
+    /// Returns error if method is `Hybrid` and hybrid key switching was never enabled.
+    pub fn try_set_key_switching_method(
+        &mut self,
+        method: KeySwitchingMethod,
+    ) -> Result<(), BfvError> {
+        if method == KeySwitchingMethod::Hybrid && self.hybrid_ksk_parameters.is_none() {
+            return Err(BfvError::InvalidDecomposition {
+                alpha: self.alpha.unwrap_or_default(),
+                moduli_count: self.ciphertext_moduli.len(),
+            });
+        }
+        self.key_switching_method = method;
+        self.update_fingerprint();
+        Ok(())
+    }
+
+    fn update_fingerprint(&mut self) {
+        #[cfg(feature = "serialize")]
+        {
+            self.fingerprint = crate::proto::compute_fingerprint(self);
+        }
+    }
+
+    /// Enables hybrid key switching with special moduli of given sizes. Alpha is set to no. of special moduli.
+    pub fn enable_hybrid_key_switching(&mut self, specialp_bits: &[usize]) {
+        self.enable_hybrid_key_switching_with_alpha(specialp_bits, specialp_bits.len());
+    }
+
+    /// Enables hybrid key switching with special moduli of given sizes and decomposes ciphertext moduli
+    /// into dnum = ceil(L/alpha) parts of `alpha` moduli each. alpha = 1 is equivalent to RNS decomposition.
+    ///
+    /// Key switching noise grows with max(Qj)/P, thus special moduli must be large enough for the
+    /// chosen alpha.
+    ///
+    /// Panics if alpha is 0.
+    pub fn enable_hybrid_key_switching_with_alpha(
+        &mut self,
+        specialp_bits: &[usize],
+        alpha: usize,
+    ) {
         let special_moduli =
             generate_primes_vec(specialp_bits, self.degree, &self.ciphertext_moduli);
+        self.try_enable_hybrid_key_switching_with_moduli(&special_moduli, alpha)
+            .unwrap();
+    }
+
+    /// Enables hybrid key switching with given special moduli and alpha
+    ///
+    /// Returns error if special moduli are empty, not NTT friendly, or not coprime to ciphertext moduli, or
+    /// if alpha is 0. alpha greater than no. of ciphertext moduli is equivalent to dnum = 1.
+    pub fn try_enable_hybrid_key_switching_with_moduli(
+        &mut self,
+        special_moduli: &[u64],
+        alpha: usize,
+    ) -> Result<(), BfvError> {
+        check_ntt_friendly(special_moduli, self.degree)?;
+        check_coprime(&self.ciphertext_moduli, special_moduli)?;
+        if alpha == 0 || special_moduli.is_empty() {
+            return Err(BfvError::InvalidDecomposition {
+                alpha,
+                moduli_count: self.ciphertext_moduli.len(),
+            });
+        }
+
+        self.enable_hybrid_key_switching_with_moduli(special_moduli.to_vec(), alpha);
+        Ok(())
+    }
+
+    fn enable_hybrid_key_switching_with_moduli(&mut self, special_moduli: Vec<u64>, alpha: usize) {
+        let dnum = (self.ciphertext_moduli.len() as f64 / alpha as f64).ceil() as usize;
         let special_moduli_ops = special_moduli
             .iter()
             .map(|pj| Modulus::new(*pj))
@@ -610,8 +870,9 @@ where
             .map(|pj| T::new(self.degree, *pj))
             .collect_vec();
 
+        self.key_switching_method = KeySwitchingMethod::Hybrid;
         self.special_moduli = Some(special_moduli);
-        self.alpha = Some(ALPHA);
+        self.alpha = Some(alpha);
         self.dnum = Some(dnum);
         self.special_moduli_ntt_ops = Some(special_moduli_ntt_ops);
         self.special_moduli_ops = Some(special_moduli_ops);
@@ -622,11 +883,12 @@ where
             .map(|level| {
                 let ksk_ctx = self.poly_ctx(&PolyType::Q, level);
                 let specialp_ctx = self.poly_ctx(&PolyType::SpecialP, level);
-                HybridKeySwitchingParameters::new(&ksk_ctx, &specialp_ctx, ALPHA)
+                HybridKeySwitchingParameters::new(&ksk_ctx, &specialp_ctx, alpha)
             })
             .collect_vec();
 
         self.hybrid_ksk_parameters = Some(params);
+        self.update_fingerprint();
     }
 
     pub fn poly_ctx(&self, poly_type: &PolyType, level: usize) -> PolyContext<'_, T> {
@@ -672,7 +934,11 @@ where
                             .as_slice(),
                         &[],
                     ),
-                    moduli_count: self.alpha.expect("SpecialP missing"),
+                    moduli_count: self
+                        .special_moduli
+                        .as_ref()
+                        .expect("SpecialP missing")
+                        .len(),
                     degree: self.degree,
                 };
                 tmp
@@ -687,7 +953,8 @@ where
                         &self.ciphertext_ntt_ops[..level_index],
                         &self.special_moduli_ntt_ops.as_ref().expect("QP missing"),
                     ),
-                    moduli_count: level_index + self.alpha.expect("QP missing"),
+                    moduli_count: level_index
+                        + self.special_moduli.as_ref().expect("QP missing").len(),
                     degree: self.degree,
                 };
                 tmp
@@ -712,6 +979,200 @@ where
     }
 }
 
+fn check_degree(degree: usize) -> Result<(), BfvError> {
+    if !degree.is_power_of_two() || degree < 16 {
+        return Err(BfvError::InvalidDegree { degree });
+    }
+    Ok(())
+}
+
+fn check_ntt_friendly(moduli: &[u64], degree: usize) -> Result<(), BfvError> {
+    if let Some(modulus) = moduli.iter().find(|m| **m % (2 * degree as u64) != 1) {
+        return Err(BfvError::ModulusNotNttFriendly {
+            modulus: *modulus,
+            degree,
+        });
+    }
+    Ok(())
+}
+
+/// Returns true if plaintext modulus `t` is an NTT-friendly prime, ie t is prime and t = 1 mod 2N,
+/// and hence supports SIMD encoding.
+fn supports_batching(t: u64, degree: usize) -> bool {
+    t % (2 * degree as u64) == 1 && probably_prime(&BigUintDig::from(t), 0)
+}
+
+/// Checks that moduli in `a` and `b` together are pairwise coprime
+fn check_coprime(a: &[u64], b: &[u64]) -> Result<(), BfvError> {
+    let moduli = a.iter().chain(b.iter()).collect_vec();
+    for (i, x) in moduli.iter().enumerate() {
+        for y in moduli[i + 1..].iter() {
+            if gcd(**x, **y) != 1 {
+                return Err(BfvError::ModuliNotCoprime { a: **x, b: **y });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Builder for `BfvParameters` that refuses parameters insecure at target security level.
+///
+/// log(QP) is calculated as sum of bit sizes of ciphertext moduli and special moduli (if hybrid key
+/// switching is enabled) and is checked against HE standard tables (see `max_log_qp`). Extension moduli
+/// are not included since they are only used for ciphertext multiplication and never for keys.
+#[derive(Clone, Debug)]
+pub struct BfvParametersBuilder<T: Ntt> {
+    degree: usize,
+    plaintext_modulus: u64,
+    ciphertext_moduli_sizes: Vec<usize>,
+    special_moduli_sizes: Option<Vec<usize>>,
+    alpha: Option<usize>,
+    security_level: SecurityLevel,
+    secret_distribution: SecretDistribution,
+    allow_insecure: bool,
+    _marker: PhantomData<T>,
+}
+
+impl<T> BfvParametersBuilder<T>
+where
+    T: Ntt,
+{
+    /// Creates builder for 128 bit security with ternary secret. Ciphertext moduli sizes must be set
+    /// before calling `build`.
+    pub fn new(degree: usize, plaintext_modulus: u64) -> BfvParametersBuilder<T> {
+        BfvParametersBuilder {
+            degree,
+            plaintext_modulus,
+            ciphertext_moduli_sizes: vec![],
+            special_moduli_sizes: None,
+            alpha: None,
+            security_level: SecurityLevel::Tc128,
+            secret_distribution: SecretDistribution::Ternary,
+            allow_insecure: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Creates builder with plaintext modulus 65537 and longest chain of 50 bit ciphertext moduli that fits
+    /// within security bound for `degree` at `security_level` after 3 50 bit special moduli for hybrid key
+    /// switching.
+    ///
+    /// Returns `None` if `degree` is not in HE standard tables or bound is too small for at least 1
+    /// ciphertext modulus.
+    pub fn preset(security_level: SecurityLevel, degree: usize) -> Option<BfvParametersBuilder<T>> {
+        let moduli_count = (max_log_qp(degree, security_level)? / 50).checked_sub(3)?;
+        if moduli_count == 0 {
+            return None;
+        }
+
+        Some(
+            BfvParametersBuilder::new(degree, 65537)
+                .ciphertext_moduli_sizes(&vec![50; moduli_count])
+                .special_moduli_sizes(&[50, 50, 50])
+                .security_level(security_level),
+        )
+    }
+
+    pub fn ciphertext_moduli_sizes(mut self, sizes: &[usize]) -> BfvParametersBuilder<T> {
+        self.ciphertext_moduli_sizes = sizes.to_vec();
+        self
+    }
+
+    /// Enables hybrid key switching with special moduli of given sizes. Otherwise parameters use BV key
+    /// switching.
+    pub fn special_moduli_sizes(mut self, sizes: &[usize]) -> BfvParametersBuilder<T> {
+        self.special_moduli_sizes = Some(sizes.to_vec());
+        self
+    }
+
+    /// Sets alpha for hybrid key switching. Defaults to no. of special moduli.
+    pub fn alpha(mut self, alpha: usize) -> BfvParametersBuilder<T> {
+        self.alpha = Some(alpha);
+        self
+    }
+
+    pub fn security_level(mut self, security_level: SecurityLevel) -> BfvParametersBuilder<T> {
+        self.security_level = security_level;
+        self
+    }
+
+    /// Sets distribution of secret key. Defaults to `SecretDistribution::Ternary`.
+    ///
+    /// HE standard tables assume uniform ternary secret, thus `build` refuses sparse secrets with hamming
+    /// weight smaller than that of ternary secret unless insecure parameters are allowed.
+    pub fn secret_distribution(
+        mut self,
+        secret_distribution: SecretDistribution,
+    ) -> BfvParametersBuilder<T> {
+        self.secret_distribution = secret_distribution;
+        self
+    }
+
+    /// Skips security check in `build`. Only use this for testing.
+    pub fn allow_insecure(mut self) -> BfvParametersBuilder<T> {
+        self.allow_insecure = true;
+        self
+    }
+
+    /// Returns log(QP) in bits
+    pub fn log_qp(&self) -> usize {
+        self.ciphertext_moduli_sizes.iter().sum::<usize>()
+            + self
+                .special_moduli_sizes
+                .as_ref()
+                .map_or(0, |sizes| sizes.iter().sum::<usize>())
+    }
+
+    /// Builds `BfvParameters`
+    ///
+    /// Returns error if ciphertext moduli sizes are not set, degree is not a power of two >= 16, special
+    /// moduli sizes are empty or alpha is 0, or if log(QP) exceeds security bound or secret is sparser than
+    /// ternary secret (see `secret_distribution`) and insecure parameters are not allowed.
+    pub fn build(&self) -> Result<BfvParameters<T>, BfvError> {
+        if self.ciphertext_moduli_sizes.is_empty() {
+            return Err(BfvError::MissingCiphertextModuli);
+        }
+        check_degree(self.degree)?;
+
+        let log_qp = self.log_qp();
+        if !self.allow_insecure {
+            let hamming_weight = self.secret_distribution.hamming_weight(self.degree);
+            if hamming_weight < SecretDistribution::Ternary.hamming_weight(self.degree) {
+                return Err(BfvError::InsecureSecretDistribution {
+                    hamming_weight,
+                    degree: self.degree,
+                });
+            }
+
+            match max_log_qp(self.degree, self.security_level) {
+                Some(bound) if log_qp <= bound => {}
+                _ => {
+                    return Err(BfvError::InsecureParameters {
+                        degree: self.degree,
+                        log_qp,
+                        security_level: self.security_level,
+                    })
+                }
+            }
+        }
+
+        let mut params = BfvParameters::new(
+            &self.ciphertext_moduli_sizes,
+            self.plaintext_modulus,
+            self.degree,
+        );
+        params.change_hamming_weight(self.secret_distribution.hamming_weight(self.degree));
+        if let Some(sizes) = self.special_moduli_sizes.as_ref() {
+            let special_moduli = generate_primes_vec(sizes, self.degree, &params.ciphertext_moduli);
+            params.try_enable_hybrid_key_switching_with_moduli(
+                &special_moduli,
+                self.alpha.unwrap_or(sizes.len()),
+            )?;
+        }
+        Ok(params)
+    }
+}
+
 #[derive(PartialEq, Clone, Debug)]
 pub struct HybridKeySwitchingParameters {
     pub(crate) dnum: usize,
@@ -737,28 +1198,8 @@ impl HybridKeySwitchingParameters {
         specialp_ctx: &PolyContext<'_, T>,
         alpha: usize,
     ) -> HybridKeySwitchingParameters {
-        let mut qj = vec![];
-        ksk_ctx
-            .iter_moduli_ops()
-            .chunks(alpha)
-            .into_iter()
-            .for_each(|modqi_parts| {
-                // Qj
-                let mut qji = BigUint::one();
-                modqi_parts.into_iter().for_each(|modqi| {
-                    qji *= modqi.modulus();
-                });
-                qj.push(qji);
-            });
-
-        let mut maxbits = qj[0].bits();
-        qj.iter().skip(1).for_each(|q| {
-            maxbits = std::cmp::max(maxbits, q.bits());
-        });
-
-        // bits in P must be more or less equal to bits in max(Qj) to assure that noise growth is key to minimum
-        let specialp_bits = specialp_ctx.big_q().bits();
-        assert!(maxbits / specialp_bits <= 1);
+        // Noise growth is kept to minimum when bits in P are more or less equal to bits in max(Qj). It isn't
+        // enforced to allow trading key size for noise with any alpha.
 
         // P is special prime
         let p = specialp_ctx.big_q();
@@ -846,7 +1287,11 @@ impl HybridKeySwitchingParameters {
                 p_hat_modq.push(((&p / modpi.modulus()) % modqj.modulus()).to_u64().unwrap());
             });
         });
-        let p_hat_modq = Array2::from_shape_vec((ksk_ctx.moduli_count, alpha), p_hat_modq).unwrap();
+        let p_hat_modq = Array2::from_shape_vec(
+            (ksk_ctx.moduli_count, specialp_ctx.moduli_count),
+            p_hat_modq,
+        )
+        .unwrap();
         let mut p_inv_modq = vec![];
         // Precompute for dividing values in basis Q by P (approx_mod_down)
         ksk_ctx.iter_moduli_ops().for_each(|modqi| {
@@ -879,7 +1324,12 @@ impl HybridKeySwitchingParameters {
 
 #[cfg(test)]
 mod tests {
-    use crate::BfvParameters;
+    use super::max_log_qp;
+    use crate::nb_theory::generate_primes_vec;
+    use crate::{
+        BfvError, BfvParameters, BfvParametersBuilder, KeySwitchingMethod, SecretDistribution,
+        SecurityLevel,
+    };
 
     #[test]
     fn trial() {
@@ -889,4 +1339,198 @@ mod tests {
         let sp = params.poly_ctx(&crate::PolyType::SpecialP, 0);
         dbg!(sp.big_q());
     }
+    #[test]
+    fn builder_refuses_insecure_parameters() {
+        // degree not in HE standard tables
+        let builder = BfvParametersBuilder::new(1 << 4, 65537).ciphertext_moduli_sizes(&[50; 3]);
+        assert_eq!(
+            builder.build(),
+            Err(BfvError::InsecureParameters {
+                degree: 1 << 4,
+                log_qp: 150,
+                security_level: SecurityLevel::Tc128
+            })
+        );
+        assert!(builder.allow_insecure().build().is_ok());
+
+        // special moduli are included in log(QP)
+        let builder = BfvParametersBuilder::new(1 << 13, 65537)
+            .ciphertext_moduli_sizes(&[50; 3])
+            .special_moduli_sizes(&[50, 50, 50]);
+        assert_eq!(builder.log_qp(), 300);
+        assert!(builder.build().is_err());
+
+        let builder = BfvParametersBuilder::new(1 << 13, 65537)
+            .ciphertext_moduli_sizes(&[60; 2])
+            .security_level(SecurityLevel::Tc256);
+        assert!(builder.build().is_err());
+        assert!(builder.security_level(SecurityLevel::Tc128).build().is_ok());
+    }
+
+    #[test]
+    fn builder_refuses_invalid_moduli_sizes() {
+        let builder = BfvParametersBuilder::new(1 << 4, 65537).allow_insecure();
+        assert_eq!(
+            builder.clone().build(),
+            Err(BfvError::MissingCiphertextModuli)
+        );
+
+        let builder = builder.ciphertext_moduli_sizes(&[50; 3]);
+        assert_eq!(
+            builder.clone().special_moduli_sizes(&[]).build(),
+            Err(BfvError::InvalidDecomposition {
+                alpha: 0,
+                moduli_count: 3
+            })
+        );
+        assert_eq!(
+            builder.clone().special_moduli_sizes(&[50]).alpha(0).build(),
+            Err(BfvError::InvalidDecomposition {
+                alpha: 0,
+                moduli_count: 3
+            })
+        );
+        assert!(builder.special_moduli_sizes(&[50]).alpha(2).build().is_ok());
+    }
+
+    #[test]
+    fn with_moduli() {
+        let params = BfvParameters::default(3, 1 << 4);
+        let params_back = BfvParameters::with_moduli(
+            &params.ciphertext_moduli,
+            &params.extension_moduli,
+            params.special_moduli.as_ref().unwrap(),
+            params.plaintext_modulus,
+            params.degree,
+        );
+        assert_eq!(params, params_back);
+
+        // degree is not a power of two >= 16
+        for degree in [8, 24] {
+            assert_eq!(
+                BfvParameters::try_with_moduli(
+                    &params.ciphertext_moduli,
+                    &params.extension_moduli,
+                    &[],
+                    65537,
+                    degree
+                ),
+                Err(BfvError::InvalidDegree { degree })
+            );
+        }
+
+        // not NTT friendly
+        let mut moduli = params.ciphertext_moduli.clone();
+        moduli[0] += 2;
+        assert_eq!(
+            BfvParameters::try_with_moduli(&moduli, &params.extension_moduli, &[], 65537, 1 << 4),
+            Err(BfvError::ModulusNotNttFriendly {
+                modulus: moduli[0],
+                degree: 1 << 4
+            })
+        );
+
+        // not coprime
+        let moduli = params.ciphertext_moduli.clone();
+        assert_eq!(
+            BfvParameters::try_with_moduli(&moduli, &moduli, &[], 65537, 1 << 4),
+            Err(BfvError::ModuliNotCoprime {
+                a: moduli[0],
+                b: moduli[0]
+            })
+        );
+
+        // extension basis too small
+        assert_eq!(
+            BfvParameters::try_with_moduli(
+                &params.ciphertext_moduli,
+                &generate_primes_vec(&[50, 50, 30], 1 << 4, &params.ciphertext_moduli),
+                &[],
+                65537,
+                1 << 4
+            ),
+            Err(BfvError::ExtensionBasisTooSmall { level: 0 })
+        );
+    }
+
+    #[test]
+    fn builder_preset() {
+        assert!(BfvParametersBuilder::preset(SecurityLevel::Tc128, 1 << 12).is_none());
+
+        let builder = BfvParametersBuilder::preset(SecurityLevel::Tc128, 1 << 13).unwrap();
+        assert!(builder.log_qp() <= max_log_qp(1 << 13, SecurityLevel::Tc128).unwrap());
+
+        let params = builder.build().unwrap();
+        assert_eq!(params.hw, 1 << 12);
+        assert!(params.special_moduli.is_some());
+    }
+
+    #[test]
+    fn builder_refuses_sparse_secret() {
+        let builder = BfvParametersBuilder::preset(SecurityLevel::Tc128, 1 << 13)
+            .unwrap()
+            .secret_distribution(SecretDistribution::Sparse(64));
+        assert_eq!(
+            builder.build(),
+            Err(BfvError::InsecureSecretDistribution {
+                hamming_weight: 64,
+                degree: 1 << 13
+            })
+        );
+
+        let params = builder.allow_insecure().build().unwrap();
+        assert_eq!(params.hw, 64);
+
+        // as dense as ternary secret
+        let builder = BfvParametersBuilder::preset(SecurityLevel::Tc128, 1 << 13)
+            .unwrap()
+            .secret_distribution(SecretDistribution::Sparse(1 << 12));
+        assert!(builder.build().is_ok());
+    }
+
+    #[test]
+    fn set_key_switching_method() {
+        let mut params = BfvParameters::new(&[50; 4], 65537, 1 << 4);
+        assert_eq!(params.key_switching_method(), KeySwitchingMethod::BV);
+        assert_eq!(
+            params.try_set_key_switching_method(KeySwitchingMethod::Hybrid),
+            Err(BfvError::InvalidDecomposition {
+                alpha: 0,
+                moduli_count: 4
+            })
+        );
+        assert_eq!(params.key_switching_method(), KeySwitchingMethod::BV);
+
+        params.enable_hybrid_key_switching(&[50, 50]);
+        assert_eq!(params.key_switching_method(), KeySwitchingMethod::Hybrid);
+        let hybrid_params = params.clone();
+
+        params
+            .try_set_key_switching_method(KeySwitchingMethod::BV)
+            .unwrap();
+        assert_eq!(params.key_switching_method(), KeySwitchingMethod::BV);
+        assert!(params.special_moduli.is_some());
+
+        params
+            .try_set_key_switching_method(KeySwitchingMethod::Hybrid)
+            .unwrap();
+        assert_eq!(params, hybrid_params);
+    }
+
+    #[test]
+    fn with_plaintext_modulus_keeps_key_switching_method() {
+        let mut params = BfvParameters::new(&[50; 4], 65537, 1 << 4);
+        params.enable_hybrid_key_switching(&[50, 50]);
+        let hybrid_params = params.try_with_plaintext_modulus(65537).unwrap();
+        assert_eq!(hybrid_params, params);
+
+        // special moduli are kept after switching to BV
+        params
+            .try_set_key_switching_method(KeySwitchingMethod::BV)
+            .unwrap();
+        let bv_params = params.try_with_plaintext_modulus(786433).unwrap();
+        assert_eq!(bv_params.key_switching_method(), KeySwitchingMethod::BV);
+        assert_eq!(bv_params.special_moduli, params.special_moduli);
+        assert_eq!(bv_params.plaintext_modulus, 786433);
+    }
 }
@@ -1,17 +1,19 @@
 use crate::poly::{Poly, Representation};
-use crate::{BfvParameters, Ciphertext, PolyType};
+use crate::{BfvError, BfvParameters, Ciphertext, NttOperator, PolyType};
 use itertools::Itertools;
 use ndarray::ArrayView1;
 use num_traits::{AsPrimitive, FromPrimitive, Unsigned, Zero};
 use traits::{Ntt, TryDecodingWithParameters, TryEncodingWithParameters};
 
-#[derive(PartialEq, Clone)]
+#[derive(PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EncodingType {
     Simd,
     Poly,
 }
 
 #[derive(PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PolyCache {
     /// Supports scalar multiplications
     Mul(PolyType),
@@ -25,6 +27,7 @@ pub enum PolyCache {
 }
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Encoding {
     pub(crate) encoding_type: EncodingType,
     pub(crate) poly_cache: PolyCache,
@@ -39,6 +42,39 @@ impl Encoding {
             level,
         }
     }
+
+    /// Coefficient encoding. Message values are set as coefficients of plaintext polynomial.
+    ///
+    /// Unlike SIMD encoding, it works with any plaintext modulus.
+    pub fn poly(level: usize, poly_cache: PolyCache) -> Encoding {
+        Encoding {
+            encoding_type: EncodingType::Poly,
+            poly_cache,
+            level,
+        }
+    }
+
+    pub fn with_level(mut self, level: usize) -> Encoding {
+        self.level = level;
+        self
+    }
+
+    pub fn with_poly_cache(mut self, poly_cache: PolyCache) -> Encoding {
+        self.poly_cache = poly_cache;
+        self
+    }
+
+    pub fn encoding_type(&self) -> EncodingType {
+        self.encoding_type.clone()
+    }
+
+    pub fn poly_cache(&self) -> &PolyCache {
+        &self.poly_cache
+    }
+
+    pub fn level(&self) -> usize {
+        self.level
+    }
 }
 
 impl Default for Encoding {
@@ -62,9 +98,28 @@ pub struct Plaintext {
 impl Plaintext {
     /// Encodes a given message `m` to plaintext using given `encoding`
     ///
-    /// Panics if `m` values length is greater than polynomial degree
+    /// Panics if `m` values length is greater than polynomial degree or if encoding is SIMD and
+    /// plaintext modulus does not support batching
     pub fn encode(m: &[u64], params: &BfvParameters, encoding: Encoding) -> Plaintext {
-        assert!(m.len() <= params.degree);
+        Plaintext::try_encode(m, params, encoding).unwrap()
+    }
+
+    /// Encodes a given message `m` to plaintext using given `encoding`
+    ///
+    /// Returns error if `m` values length is greater than polynomial degree or if encoding is SIMD and
+    /// plaintext modulus does not support batching.
+    pub fn try_encode(
+        m: &[u64],
+        params: &BfvParameters,
+        encoding: Encoding,
+    ) -> Result<Plaintext, BfvError> {
+        if m.len() > params.degree {
+            return Err(BfvError::MessageTooLong {
+                len: m.len(),
+                degree: params.degree,
+            });
+        }
+        let plaintext_ntt_op = check_batching(params, &encoding)?;
 
         let mut m1 = vec![0u64; params.degree];
         let mut m = m.to_vec();
@@ -78,10 +133,19 @@ impl Plaintext {
         });
         params.plaintext_modulus_op.reduce_vec(&mut m1);
 
-        if encoding.encoding_type == EncodingType::Simd {
-            params.plaintext_ntt_op.backward(&mut m1);
+        if let Some(ntt_op) = plaintext_ntt_op {
+            ntt_op.backward(&mut m1);
         }
 
+        Ok(Plaintext::with_poly_cache(m1, params, encoding))
+    }
+
+    /// Returns plaintext for encoded message `m1` with polynomials cached as specified by `encoding`
+    pub(crate) fn with_poly_cache(
+        m1: Vec<u64>,
+        params: &BfvParameters,
+        encoding: Encoding,
+    ) -> Plaintext {
         // convert m to polynomial with poly context at specific level
         let (mul_poly, add_sub_poly) = {
             match &encoding.poly_cache {
@@ -124,11 +188,31 @@ impl Plaintext {
         encoding: Encoding,
         params: &BfvParameters,
     ) -> Vec<T> {
-        assert!(self.encoding.is_none());
+        self.try_decode(encoding, params).unwrap()
+    }
+
+    /// Decodes plaintext (output of decryption) using given `encoding`
+    ///
+    /// Returns error if encoding is SIMD and plaintext modulus does not support batching, or if
+    /// plaintext records a different encoding type than `encoding`.
+    pub fn try_decode<T: Zero + Clone + FromPrimitive>(
+        &self,
+        encoding: Encoding,
+        params: &BfvParameters,
+    ) -> Result<Vec<T>, BfvError> {
+        if let Some(recorded) = self.encoding.as_ref() {
+            if recorded.encoding_type != encoding.encoding_type {
+                return Err(BfvError::EncodingMismatch {
+                    expected: recorded.encoding_type.clone(),
+                    found: encoding.encoding_type,
+                });
+            }
+        }
+        let plaintext_ntt_op = check_batching(params, &encoding)?;
 
         let mut m1 = self.m.clone();
-        if encoding.encoding_type == EncodingType::Simd {
-            params.plaintext_ntt_op.forward(&mut m1);
+        if let Some(ntt_op) = plaintext_ntt_op {
+            ntt_op.forward(&mut m1);
         }
 
         let mut m = vec![T::zero(); params.degree];
@@ -140,7 +224,7 @@ impl Plaintext {
             }
         }
 
-        m
+        Ok(m)
     }
 
     /// Returns message polynomial `m` scaled by Q/t
@@ -181,16 +265,35 @@ impl Plaintext {
     }
 
     pub fn mul_poly_type(&self) -> PolyType {
-        match &self.encoding.as_ref().unwrap().poly_cache {
-            PolyCache::Mul(poly_type) => poly_type.clone(),
-            _ => {
-                panic!("PolyCache not Mul")
-            }
+        self.try_mul_poly_type().unwrap()
+    }
+
+    /// Returns `PolyType` of the cached mul poly
+    ///
+    /// Returns error if plaintext was not encoded with `PolyCache` that supports multiplication
+    pub fn try_mul_poly_type(&self) -> Result<PolyType, BfvError> {
+        match &self.try_encoding()?.poly_cache {
+            PolyCache::Mul(poly_type) | PolyCache::All(poly_type, _) => Ok(poly_type.clone()),
+            _ => Err(BfvError::MissingMulPoly),
         }
     }
 
     pub fn level(&self) -> usize {
-        self.encoding.as_ref().unwrap().level
+        self.try_level().unwrap()
+    }
+
+    pub fn try_level(&self) -> Result<usize, BfvError> {
+        Ok(self.try_encoding()?.level)
+    }
+
+    /// Returns encoding of the plaintext. For output of decryption, returns encoding (with `PolyCache::None`)
+    /// that the plaintext should be decoded with.
+    pub fn encoding(&self) -> Option<&Encoding> {
+        self.encoding.as_ref()
+    }
+
+    pub(crate) fn try_encoding(&self) -> Result<&Encoding, BfvError> {
+        self.encoding.as_ref().ok_or(BfvError::MissingEncoding)
     }
 
     pub fn supports_mul_poly(&self) -> bool {
@@ -198,11 +301,21 @@ impl Plaintext {
     }
 
     pub fn add_sub_poly_ref(&self) -> &Poly {
-        self.add_sub_poly.as_ref().expect("Missing add_sub poly")
+        self.try_add_sub_poly_ref().unwrap()
+    }
+
+    pub fn try_add_sub_poly_ref(&self) -> Result<&Poly, BfvError> {
+        self.add_sub_poly
+            .as_ref()
+            .ok_or(BfvError::MissingAddSubPoly)
     }
 
     pub fn mul_poly_ref(&self) -> &Poly {
-        self.mul_poly.as_ref().expect("Missing mul poly")
+        self.try_mul_poly_ref().unwrap()
+    }
+
+    pub fn try_mul_poly_ref(&self) -> Result<&Poly, BfvError> {
+        self.mul_poly.as_ref().ok_or(BfvError::MissingMulPoly)
     }
 
     pub fn move_mul_poly(self) -> Poly {
@@ -214,6 +327,26 @@ impl Plaintext {
     }
 }
 
+/// Returns plaintext NTT operator if `encoding` is SIMD, `None` otherwise
+///
+/// Returns error if encoding is SIMD and plaintext modulus does not support batching
+fn check_batching<'a>(
+    params: &'a BfvParameters,
+    encoding: &Encoding,
+) -> Result<Option<&'a NttOperator>, BfvError> {
+    if encoding.encoding_type != EncodingType::Simd {
+        return Ok(None);
+    }
+    params
+        .plaintext_ntt_op
+        .as_ref()
+        .map(Some)
+        .ok_or(BfvError::BatchingNotSupported {
+            plaintext_modulus: params.plaintext_modulus,
+            degree: params.degree,
+        })
+}
+
 impl TryEncodingWithParameters<&[u32]> for Plaintext {
     type Encoding = Encoding;
     type Parameters = BfvParameters;
@@ -255,5 +388,150 @@ impl<'a> TryDecodingWithParameters<&'a Plaintext> for Vec<u32> {
     }
 }
 
+impl TryEncodingWithParameters<&[i64]> for Plaintext {
+    type Encoding = Encoding;
+    type Parameters = BfvParameters;
+
+    /// Values must be in range [-t/2, t/2). Values outside the range are reduced modulo t.
+    fn try_encoding_with_parameters(
+        value: &[i64],
+        parameters: &Self::Parameters,
+        encoding: Self::Encoding,
+    ) -> Self {
+        let value_u64 = value
+            .iter()
+            .map(|v| signed_to_mod_t(*v, parameters.plaintext_modulus))
+            .collect_vec();
+        Self::encode(&value_u64, parameters, encoding)
+    }
+}
+
+impl TryEncodingWithParameters<&[i32]> for Plaintext {
+    type Encoding = Encoding;
+    type Parameters = BfvParameters;
+
+    /// Values must be in range [-t/2, t/2). Values outside the range are reduced modulo t.
+    fn try_encoding_with_parameters(
+        value: &[i32],
+        parameters: &Self::Parameters,
+        encoding: Self::Encoding,
+    ) -> Self {
+        let value_u64 = value
+            .iter()
+            .map(|v| signed_to_mod_t(*v as i64, parameters.plaintext_modulus))
+            .collect_vec();
+        Self::encode(&value_u64, parameters, encoding)
+    }
+}
+
+impl<'a> TryDecodingWithParameters<&'a Plaintext> for Vec<i64> {
+    type Encoding = Encoding;
+    type Parameters = &'a BfvParameters;
+
+    /// Returns values in range [-t/2, t/2)
+    fn try_decoding_with_parameters(
+        value: &'a Plaintext,
+        parameters: Self::Parameters,
+        encoding: Self::Encoding,
+    ) -> Vec<i64> {
+        value
+            .decode::<u64>(encoding, parameters)
+            .iter()
+            .map(|v| mod_t_to_signed(*v, parameters.plaintext_modulus))
+            .collect()
+    }
+}
+
+impl<'a> TryDecodingWithParameters<&'a Plaintext> for Vec<i32> {
+    type Encoding = Encoding;
+    type Parameters = &'a BfvParameters;
+
+    /// Returns values in range [-t/2, t/2)
+    ///
+    /// Panics if plaintext modulus is greater than 2^32
+    fn try_decoding_with_parameters(
+        value: &'a Plaintext,
+        parameters: Self::Parameters,
+        encoding: Self::Encoding,
+    ) -> Vec<i32> {
+        assert!(parameters.plaintext_modulus <= 1 << 32);
+        value
+            .decode::<u64>(encoding, parameters)
+            .iter()
+            .map(|v| mod_t_to_signed(*v, parameters.plaintext_modulus) as i32)
+            .collect()
+    }
+}
+
+/// Maps signed value to [0, t)
+fn signed_to_mod_t(v: i64, t: u64) -> u64 {
+    v.rem_euclid(t as i64) as u64
+}
+
+/// Maps value in [0, t) to its centered representative in [-t/2, t/2)
+fn mod_t_to_signed(v: u64, t: u64) -> i64 {
+    if v >= t.div_ceil(2) {
+        v as i64 - t as i64
+    } else {
+        v as i64
+    }
+}
+
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn signed_encoding_decoding() {
+        let mut rng = thread_rng();
+        for (t, degree) in [(65537, 1 << 4), (1 << 16, 1 << 4)] {
+            let params = BfvParameters::new(&[50; 3], t, degree);
+            let bound = (t / 2) as i64;
+
+            let mut encodings = vec![Encoding::poly(0, PolyCache::None)];
+            if params.supports_batching() {
+                encodings.push(Encoding::default());
+            }
+
+            for encoding in encodings {
+                let m = (0..degree)
+                    .map(|_| rng.gen_range(-bound..bound))
+                    .collect_vec();
+                let pt = Plaintext::try_encoding_with_parameters(
+                    m.as_slice(),
+                    &params,
+                    encoding.clone(),
+                );
+                let m_back =
+                    Vec::<i64>::try_decoding_with_parameters(&pt, &params, encoding.clone());
+                assert_eq!(m, m_back);
+
+                let m = m.iter().map(|v| (*v / 2) as i32).collect_vec();
+                let pt = Plaintext::try_encoding_with_parameters(
+                    m.as_slice(),
+                    &params,
+                    encoding.clone(),
+                );
+                let m_back = Vec::<i32>::try_decoding_with_parameters(&pt, &params, encoding);
+                assert_eq!(m, m_back);
+            }
+        }
+    }
+
+    #[test]
+    fn encode_rejects_message_longer_than_degree() {
+        let params = BfvParameters::new(&[50; 3], 65537, 1 << 4);
+        let m = vec![1; params.degree + 1];
+        for encoding in [Encoding::default(), Encoding::poly(0, PolyCache::None)] {
+            assert!(matches!(
+                Plaintext::try_encode(&m, &params, encoding.clone()),
+                Err(BfvError::MessageTooLong {
+                    len: 17,
+                    degree: 16
+                })
+            ));
+            assert!(Plaintext::try_encode(&m[1..], &params, encoding).is_ok());
+        }
+    }
+}
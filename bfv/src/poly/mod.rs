@@ -6,6 +6,7 @@ pub mod poly_context;
 pub use poly_context::PolyContext;
 
 #[derive(Clone, PartialEq, Debug, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Representation {
     Evaluation,
     Coefficient,
@@ -54,6 +55,7 @@ impl Substitution {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Poly {
     pub(crate) coefficients: Array2<u64>,
     pub(crate) representation: Representation,
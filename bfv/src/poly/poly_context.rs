@@ -12,7 +12,6 @@ use num_traits::{identities::One, ToPrimitive, Zero};
 use rand::{CryptoRng, RngCore, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 use seq_macro::seq;
-use std::mem::{self, MaybeUninit};
 use traits::Ntt;
 
 #[derive(PartialEq)]
@@ -231,9 +230,16 @@ where
                     let xi_hi = xi >> b;
                     let xi_lo = xi - (xi_hi << b);
 
-                    // FIXME: this will fail in debug mode since xi_lo and xi_hi are almost always greater than t (ie plaintext modulus)
-                    rational_sum = t.add_mod_fast(rational_sum, t.mul_mod_fast(xi_lo, *rational));
-                    rational_sum = t.add_mod_fast(rational_sum, t.mul_mod_fast(xi_hi, *brational));
+                    // xi_lo and xi_hi are usually greater than t (ie plaintext modulus), hence products are reduced
+                    // with 128 bit barrett reduction which is valid for inputs of any size.
+                    rational_sum = t.add_mod_fast(
+                        rational_sum,
+                        t.barret_reduction_u128(xi_lo as u128 * *rational as u128),
+                    );
+                    rational_sum = t.add_mod_fast(
+                        rational_sum,
+                        t.barret_reduction_u128(xi_hi as u128 * *brational as u128),
+                    );
 
                     fractional_sum += xi_lo.to_f64().unwrap() * fractional;
                     fractional_sum += xi_hi.to_f64().unwrap() * bfractional;
@@ -626,28 +632,26 @@ where
         p_moduli_ops: &[Modulus],
     ) -> Array2<u64> {
         debug_assert!(q_moduli_ops.len() == q_coefficients.shape()[0]);
-        debug_assert!(q_moduli_ops.len() <= 3);
 
         let mut p_coeffs = Array2::<u64>::uninit((p_moduli_ops.len(), degree));
 
         let p_size = p_moduli_ops.len();
         let q_size = q_coefficients.shape()[0];
+
+        // q_size can be any value (for ex, alpha in hybrid key switching), thus tmp is allocated on heap once
+        // and reused for every chunk of 8 coefficients.
+        let mut tmp = vec![0u64; q_size * 8];
         unsafe {
             for ri in (0..degree).step_by(8) {
-                let mut tmp: [MaybeUninit<u64>; 3 * 8] = MaybeUninit::uninit().assume_init();
-
                 for i in 0..q_size {
                     let modq = q_moduli_ops.get_unchecked(i);
                     let op = *q_hat_inv_modq.get_unchecked(i);
 
                     seq!(N in 0..8 {
-                        tmp.get_unchecked_mut(i*8+N)
-                        .write(modq.mul_mod_fast(*q_coefficients.uget((i, ri+N)), op));
+                        *tmp.get_unchecked_mut(i*8+N) = modq.mul_mod_fast(*q_coefficients.uget((i, ri+N)), op);
                     });
                 }
-                // tmp.set_len(q_size);
 
-                let tmp = mem::transmute::<_, [u64; 3 * 8]>(tmp);
                 for j in 0..p_size {
                     seq!(N in 0..8 {
                         let mut s~N = 0u128;
@@ -828,6 +832,48 @@ where
         }
     }
 
+    /// Multiplies each row of `poly` by corresponding scalar in `scalars`.
+    ///
+    /// Scalars must be reduced by their respective moduli.
+    pub fn scalar_mul_assign(&self, poly: &mut Poly, scalars: &[u64]) {
+        debug_assert!(scalars.len() == self.moduli_count);
+        izip!(
+            poly.coefficients.outer_iter_mut(),
+            self.iter_moduli_ops(),
+            scalars.iter()
+        )
+        .for_each(|(mut coeffs, modqi, b)| {
+            modqi.scalar_mul_mod_fast_vec(coeffs.as_slice_mut().unwrap(), *b);
+        });
+    }
+
+    /// Adds constant polynomial with constant term `scalars` (in RNS) to `poly`.
+    ///
+    /// In `Coefficient` representation only the constant coefficient changes whereas in
+    /// `Evaluation` representation all coefficients change. Scalars must be reduced by their respective moduli.
+    pub fn add_scalar_assign(&self, poly: &mut Poly, scalars: &[u64]) {
+        debug_assert!(scalars.len() == self.moduli_count);
+        let representation = poly.representation.clone();
+        izip!(
+            poly.coefficients.outer_iter_mut(),
+            self.iter_moduli_ops(),
+            scalars.iter()
+        )
+        .for_each(|(mut coeffs, modqi, b)| match representation {
+            Representation::Coefficient => {
+                coeffs[0] = modqi.add_mod_fast(coeffs[0], *b);
+            }
+            Representation::Evaluation => {
+                coeffs.iter_mut().for_each(|v| {
+                    *v = modqi.add_mod_fast(*v, *b);
+                });
+            }
+            Representation::Unknown => {
+                panic!("Cannot add scalar to polynomial in Unknown representation")
+            }
+        });
+    }
+
     pub fn add_assign(&self, lhs: &mut Poly, rhs: &Poly) {
         debug_assert!(lhs.representation == rhs.representation);
 
@@ -0,0 +1,200 @@
+// @generated by prost-build from bfv.proto. Do not edit.
+// Regenerate by copying `bfv.rs` generated in OUT_DIR with `build-protos` feature.
+// bfv.proto sha256: effd7f783e5d6b3cb76be46e793a33ccac9cf019b922ed7325b240cf0491e39a
+
+/// Fingerprint of `Parameters` is embedded in every ciphertext and key so that
+/// objects produced under different parameters are rejected on deserialization.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Parameters {
+    #[prost(uint32, tag = "1")]
+    pub degree: u32,
+    #[prost(uint64, tag = "2")]
+    pub plaintext_modulus: u64,
+    #[prost(uint64, repeated, tag = "3")]
+    pub ciphertext_moduli: ::prost::alloc::vec::Vec<u64>,
+    #[prost(uint64, repeated, tag = "4")]
+    pub extension_moduli: ::prost::alloc::vec::Vec<u64>,
+    /// special moduli are only present if hybrid key switching is enabled
+    #[prost(uint64, repeated, tag = "5")]
+    pub special_moduli: ::prost::alloc::vec::Vec<u64>,
+    #[prost(uint32, tag = "6")]
+    pub variance: u32,
+    #[prost(uint32, tag = "7")]
+    pub hw: u32,
+    /// alpha for hybrid key switching. Only present if special moduli are present.
+    #[prost(uint32, tag = "8")]
+    pub alpha: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Poly {
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    pub coefficients: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SecretKey {
+    #[prost(bytes = "vec", tag = "1")]
+    pub coefficients: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub fingerprint: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PublicKey {
+    #[prost(message, optional, tag = "1")]
+    pub c0: ::core::option::Option<Poly>,
+    /// c1 is only present if seed is missing
+    #[prost(message, optional, tag = "2")]
+    pub c1: ::core::option::Option<Poly>,
+    #[prost(bytes = "vec", optional, tag = "3")]
+    pub seed: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    #[prost(uint32, tag = "4")]
+    pub level: u32,
+    #[prost(bytes = "vec", tag = "5")]
+    pub fingerprint: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Ciphertext {
+    #[prost(message, repeated, tag = "1")]
+    pub c: ::prost::alloc::vec::Vec<Poly>,
+    #[prost(uint32, tag = "2")]
+    pub level: u32,
+    #[prost(bytes = "vec", optional, tag = "3")]
+    pub seed: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    #[prost(bytes = "vec", tag = "4")]
+    pub fingerprint: ::prost::alloc::vec::Vec<u8>,
+    #[prost(enumeration = "EncodingType", tag = "5")]
+    pub encoding_type: i32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HybridKeySwitchingKey {
+    #[prost(message, repeated, tag = "1")]
+    pub c0s: ::prost::alloc::vec::Vec<Poly>,
+    /// repeated is already optional
+    #[prost(message, repeated, tag = "2")]
+    pub c1s: ::prost::alloc::vec::Vec<Poly>,
+    #[prost(bytes = "vec", optional, tag = "3")]
+    pub seed: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BvKeySwitchingKey {
+    #[prost(message, repeated, tag = "1")]
+    pub c0s: ::prost::alloc::vec::Vec<Poly>,
+    /// c1s are only present if seed is missing
+    #[prost(message, repeated, tag = "2")]
+    pub c1s: ::prost::alloc::vec::Vec<Poly>,
+    #[prost(bytes = "vec", optional, tag = "3")]
+    pub seed: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KeySwitchingKey {
+    #[prost(oneof = "key_switching_key::Ksk", tags = "1, 2")]
+    pub ksk: ::core::option::Option<key_switching_key::Ksk>,
+}
+/// Nested message and enum types in `KeySwitchingKey`.
+pub mod key_switching_key {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Ksk {
+        #[prost(message, tag = "1")]
+        HybridKsk(super::HybridKeySwitchingKey),
+        #[prost(message, tag = "2")]
+        BvKsk(super::BvKeySwitchingKey),
+    }
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RelinearizationKey {
+    #[prost(uint32, tag = "2")]
+    pub level: u32,
+    #[prost(bytes = "vec", tag = "3")]
+    pub fingerprint: ::prost::alloc::vec::Vec<u8>,
+    /// keys for s^3, s^4, ...
+    #[prost(message, repeated, tag = "5")]
+    pub higher_ksks: ::prost::alloc::vec::Vec<KeySwitchingKey>,
+    /// key for s^2
+    #[prost(oneof = "relinearization_key::Ksk", tags = "1, 4")]
+    pub ksk: ::core::option::Option<relinearization_key::Ksk>,
+}
+/// Nested message and enum types in `RelinearizationKey`.
+pub mod relinearization_key {
+    /// key for s^2
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Ksk {
+        #[prost(message, tag = "1")]
+        HybridKsk(super::HybridKeySwitchingKey),
+        #[prost(message, tag = "4")]
+        BvKsk(super::BvKeySwitchingKey),
+    }
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GaloisKey {
+    #[prost(uint32, tag = "1")]
+    pub exponent: u32,
+    #[prost(uint32, tag = "3")]
+    pub level: u32,
+    #[prost(bytes = "vec", tag = "4")]
+    pub fingerprint: ::prost::alloc::vec::Vec<u8>,
+    #[prost(oneof = "galois_key::Ksk", tags = "2, 5")]
+    pub ksk: ::core::option::Option<galois_key::Ksk>,
+}
+/// Nested message and enum types in `GaloisKey`.
+pub mod galois_key {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Ksk {
+        #[prost(message, tag = "2")]
+        HybridKsk(super::HybridKeySwitchingKey),
+        #[prost(message, tag = "5")]
+        BvKsk(super::BvKeySwitchingKey),
+    }
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EvaluationKey {
+    /// RelinearizartionKeys and GaliosKeys are stored in arbitrary order.
+    /// This means that if there are two instances of EvaluationKey both with
+    /// same rlks and rtgs, their bytes might not exactly match.
+    #[prost(message, repeated, tag = "1")]
+    pub rlks: ::prost::alloc::vec::Vec<RelinearizationKey>,
+    #[prost(message, repeated, tag = "2")]
+    pub rtgs: ::prost::alloc::vec::Vec<GaloisKey>,
+    #[prost(int32, repeated, tag = "3")]
+    pub rot_indices: ::prost::alloc::vec::Vec<i32>,
+    #[prost(bytes = "vec", tag = "4")]
+    pub fingerprint: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum EncodingType {
+    Simd = 0,
+    Poly = 1,
+}
+impl EncodingType {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            EncodingType::Simd => "SIMD",
+            EncodingType::Poly => "POLY",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "SIMD" => Some(Self::Simd),
+            "POLY" => Some(Self::Poly),
+            _ => None,
+        }
+    }
+}
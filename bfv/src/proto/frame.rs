@@ -0,0 +1,447 @@
+//! Versioned framing of serialized objects.
+//!
+//! Every object is stored as (all integers are little-endian)
+//!
+//! | field       | bytes  |                                                        |
+//! |-------------|--------|--------------------------------------------------------|
+//! | magic       | 4      | `BFV\0`                                                |
+//! | version     | 2      | `FORMAT_VERSION`                                       |
+//! | object type | 1      | tag of `ObjectType`                                    |
+//! | fingerprint | 32     | `BfvParameters::fingerprint`                           |
+//! | level       | 4      | level of object. 0 for `SecretKey` and `EvaluationKey` |
+//! | length      | 8      | length of payload                                      |
+//! | payload     | length | protobuf encoded object                                |
+//! | checksum    | 32     | SHA-256 digest of all preceding bytes                  |
+//!
+//! Deserialization checks the frame before converting the payload, which in turn validates the
+//! payload against parameters. Thus malformed or mismatched bytes are rejected with `BfvError`
+//! instead of a panic.
+
+use super::{check_fingerprint, compressed_ciphertext_proto, invalid, proto};
+use crate::{
+    BfvError, BfvParameters, Ciphertext, EvaluationKey, GaloisKey, PublicKey, RelinearizationKey,
+    SecretKey,
+};
+use prost::Message;
+use sha2::{Digest, Sha256};
+use traits::TryFromWithParameters;
+
+/// Type of object stored in serialized bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectType {
+    SecretKey = 1,
+    PublicKey = 2,
+    Ciphertext = 3,
+    RelinearizationKey = 4,
+    GaloisKey = 5,
+    EvaluationKey = 6,
+}
+
+const MAGIC: [u8; 4] = *b"BFV\0";
+/// Version of the wire format. Bumped on every incompatible change to the frame or `bfv.proto`.
+pub const FORMAT_VERSION: u16 = 1;
+
+const FINGERPRINT_SIZE: usize = 32;
+const CHECKSUM_SIZE: usize = 32;
+const HEADER_SIZE: usize = 4 + 2 + 1 + FINGERPRINT_SIZE + 4 + 8;
+
+/// Returns `payload` framed with header and checksum
+fn encode_frame<M: Message>(
+    object_type: ObjectType,
+    level: usize,
+    payload: &M,
+    params: &BfvParameters,
+) -> Vec<u8> {
+    let payload = payload.encode_to_vec();
+
+    let mut bytes = Vec::with_capacity(HEADER_SIZE + payload.len() + CHECKSUM_SIZE);
+    bytes.extend_from_slice(&MAGIC);
+    bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    bytes.push(object_type as u8);
+    bytes.extend_from_slice(params.fingerprint());
+    bytes.extend_from_slice(&(level as u32).to_le_bytes());
+    bytes.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&payload);
+    let checksum = Sha256::digest(&bytes);
+    bytes.extend_from_slice(&checksum);
+    bytes
+}
+
+/// Checks frame and returns level and decoded payload
+fn decode_frame<M: Message + Default>(
+    bytes: &[u8],
+    object_type: ObjectType,
+    params: &BfvParameters,
+) -> Result<(usize, M), BfvError> {
+    if bytes.len() < HEADER_SIZE + CHECKSUM_SIZE {
+        return Err(BfvError::TruncatedBytes {
+            expected: HEADER_SIZE + CHECKSUM_SIZE,
+            found: bytes.len(),
+        });
+    }
+
+    let (header, rest) = bytes.split_at(HEADER_SIZE);
+    if header[..4] != MAGIC {
+        return Err(BfvError::InvalidMagic);
+    }
+    let version = u16::from_le_bytes([header[4], header[5]]);
+    if version != FORMAT_VERSION {
+        return Err(BfvError::UnsupportedVersion { version });
+    }
+    if header[6] != object_type as u8 {
+        return Err(BfvError::ObjectTypeMismatch {
+            expected: object_type as u8,
+            found: header[6],
+        });
+    }
+
+    // length is untrusted, thus frame of length that overflows is truncated
+    let length = u64::from_le_bytes(header[HEADER_SIZE - 8..].try_into().unwrap());
+    let expected = length
+        .checked_add((HEADER_SIZE + CHECKSUM_SIZE) as u64)
+        .ok_or(BfvError::TruncatedBytes {
+            expected: usize::MAX,
+            found: bytes.len(),
+        })?;
+    if (bytes.len() as u64) < expected {
+        return Err(BfvError::TruncatedBytes {
+            expected: expected.try_into().unwrap_or(usize::MAX),
+            found: bytes.len(),
+        });
+    }
+    if (bytes.len() as u64) > expected {
+        return Err(BfvError::InvalidObject {
+            reason: format!("{} trailing bytes", bytes.len() as u64 - expected),
+        });
+    }
+
+    let (payload, checksum) = rest.split_at(length as usize);
+    if Sha256::digest(&bytes[..HEADER_SIZE + payload.len()]).as_slice() != checksum {
+        return Err(BfvError::ChecksumMismatch);
+    }
+    check_fingerprint(&header[7..7 + FINGERPRINT_SIZE], params)?;
+
+    let level =
+        u32::from_le_bytes(header[7 + FINGERPRINT_SIZE..][..4].try_into().unwrap()) as usize;
+    let value = M::decode(payload).map_err(|e| invalid(e.to_string()))?;
+    Ok((level, value))
+}
+
+/// Returns error if level of deserialized object does not match level in frame header
+fn check_frame_level(level: usize, frame_level: usize) -> Result<(), BfvError> {
+    if level != frame_level {
+        return Err(BfvError::LevelMismatch {
+            expected: frame_level,
+            found: level,
+        });
+    }
+    Ok(())
+}
+
+impl SecretKey {
+    /// Serializes secret key to framed bytes
+    pub fn to_bytes(&self, params: &BfvParameters) -> Vec<u8> {
+        let value = proto::SecretKey::try_from_with_parameters(self, params).unwrap();
+        encode_frame(ObjectType::SecretKey, 0, &value, params)
+    }
+
+    /// Deserializes secret key from bytes produced by [SecretKey::to_bytes]
+    pub fn try_from_bytes(bytes: &[u8], params: &BfvParameters) -> Result<SecretKey, BfvError> {
+        let (_, value) = decode_frame::<proto::SecretKey>(bytes, ObjectType::SecretKey, params)?;
+        SecretKey::try_from_with_parameters(&value, params)
+    }
+}
+
+impl PublicKey {
+    /// Serializes public key to framed bytes
+    pub fn to_bytes(&self, params: &BfvParameters) -> Vec<u8> {
+        let value = proto::PublicKey::try_from_with_parameters(self, params).unwrap();
+        encode_frame(ObjectType::PublicKey, self.level, &value, params)
+    }
+
+    /// Deserializes public key from bytes produced by [PublicKey::to_bytes]
+    pub fn try_from_bytes(bytes: &[u8], params: &BfvParameters) -> Result<PublicKey, BfvError> {
+        let (frame_level, value) =
+            decode_frame::<proto::PublicKey>(bytes, ObjectType::PublicKey, params)?;
+        let pk = PublicKey::try_from_with_parameters(&value, params)?;
+        check_frame_level(pk.level, frame_level)?;
+        Ok(pk)
+    }
+}
+
+impl Ciphertext {
+    /// Serializes ciphertext to framed bytes. Like [Ciphertext::to_compressed_bytes], `c[1]` is
+    /// replaced by seed if ciphertext has a valid seed.
+    ///
+    /// Panics if ciphertext is not in `Coefficient` representation or its poly type is not `Q`.
+    pub fn to_bytes(&self, params: &BfvParameters) -> Vec<u8> {
+        let value = compressed_ciphertext_proto(self, params);
+        encode_frame(ObjectType::Ciphertext, self.level, &value, params)
+    }
+
+    /// Deserializes ciphertext from bytes produced by [Ciphertext::to_bytes]
+    pub fn try_from_bytes(bytes: &[u8], params: &BfvParameters) -> Result<Ciphertext, BfvError> {
+        let (frame_level, value) =
+            decode_frame::<proto::Ciphertext>(bytes, ObjectType::Ciphertext, params)?;
+        let ct = Ciphertext::try_from_with_parameters(&value, params)?;
+        check_frame_level(ct.level, frame_level)?;
+        Ok(ct)
+    }
+}
+
+impl RelinearizationKey {
+    /// Serializes relinearization key to framed bytes
+    pub fn to_bytes(&self, params: &BfvParameters) -> Vec<u8> {
+        let value = proto::RelinearizationKey::try_from_with_parameters(self, params).unwrap();
+        encode_frame(ObjectType::RelinearizationKey, self.level, &value, params)
+    }
+
+    /// Deserializes relinearization key from bytes produced by [RelinearizationKey::to_bytes]
+    pub fn try_from_bytes(
+        bytes: &[u8],
+        params: &BfvParameters,
+    ) -> Result<RelinearizationKey, BfvError> {
+        let (frame_level, value) = decode_frame::<proto::RelinearizationKey>(
+            bytes,
+            ObjectType::RelinearizationKey,
+            params,
+        )?;
+        let rlk = RelinearizationKey::try_from_with_parameters(&value, params)?;
+        check_frame_level(rlk.level, frame_level)?;
+        Ok(rlk)
+    }
+}
+
+impl GaloisKey {
+    /// Serializes galois key to framed bytes
+    pub fn to_bytes(&self, params: &BfvParameters) -> Vec<u8> {
+        let value = proto::GaloisKey::try_from_with_parameters(self, params).unwrap();
+        encode_frame(ObjectType::GaloisKey, self.level, &value, params)
+    }
+
+    /// Deserializes galois key from bytes produced by [GaloisKey::to_bytes]
+    pub fn try_from_bytes(bytes: &[u8], params: &BfvParameters) -> Result<GaloisKey, BfvError> {
+        let (frame_level, value) =
+            decode_frame::<proto::GaloisKey>(bytes, ObjectType::GaloisKey, params)?;
+        let rtg = GaloisKey::try_from_with_parameters(&value, params)?;
+        check_frame_level(rtg.level, frame_level)?;
+        Ok(rtg)
+    }
+}
+
+impl EvaluationKey {
+    /// Serializes evaluation key to framed bytes
+    pub fn to_bytes(&self, params: &BfvParameters) -> Vec<u8> {
+        let value = proto::EvaluationKey::try_from_with_parameters(self, params).unwrap();
+        encode_frame(ObjectType::EvaluationKey, 0, &value, params)
+    }
+
+    /// Deserializes evaluation key from bytes produced by [EvaluationKey::to_bytes]
+    pub fn try_from_bytes(bytes: &[u8], params: &BfvParameters) -> Result<EvaluationKey, BfvError> {
+        let (_, value) =
+            decode_frame::<proto::EvaluationKey>(bytes, ObjectType::EvaluationKey, params)?;
+        EvaluationKey::try_from_with_parameters(&value, params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Encoding, Evaluator};
+    use rand::thread_rng;
+
+    /// Replaces payload of `bytes` and recomputes checksum
+    fn reframe(bytes: &[u8], payload: &[u8]) -> Vec<u8> {
+        let mut out = bytes[..HEADER_SIZE].to_vec();
+        out[HEADER_SIZE - 8..].copy_from_slice(&(payload.len() as u64).to_le_bytes());
+        out.extend_from_slice(payload);
+        let checksum = Sha256::digest(&out);
+        out.extend_from_slice(&checksum);
+        out
+    }
+
+    #[test]
+    fn framed_bytes_roundtrip() {
+        let mut rng = thread_rng();
+        for params in [
+            BfvParameters::default(5, 1 << 4),
+            BfvParameters::new(&[50; 4], 65537, 1 << 4),
+        ] {
+            let sk = SecretKey::random_with_params(&params, &mut rng);
+            assert_eq!(
+                SecretKey::try_from_bytes(&sk.to_bytes(&params), &params).unwrap(),
+                sk
+            );
+
+            let pk = PublicKey::new(&params, &sk, 1, &mut rng);
+            assert_eq!(
+                PublicKey::try_from_bytes(&pk.to_bytes(&params), &params).unwrap(),
+                pk
+            );
+
+            let ek = EvaluationKey::new(&params, &sk, &[0, 1], &[0, 1], &[1, -1], &mut rng);
+            let ek_back = EvaluationKey::try_from_bytes(&ek.to_bytes(&params), &params).unwrap();
+            assert_eq!(ek_back, ek);
+
+            let rlk = &ek.rlks[&1];
+            assert_eq!(
+                &RelinearizationKey::try_from_bytes(&rlk.to_bytes(&params), &params).unwrap(),
+                rlk
+            );
+            let rtg = &ek.rtgs[&(-1, 0)];
+            assert_eq!(
+                &GaloisKey::try_from_bytes(&rtg.to_bytes(&params), &params).unwrap(),
+                rtg
+            );
+
+            let m = params
+                .plaintext_modulus_op
+                .random_vec(params.degree, &mut rng);
+            let evaluator = Evaluator::new(params);
+            let pt = evaluator.plaintext_encode(&m, Encoding::default());
+            let ct = evaluator.encrypt(&sk, &pt, &mut rng);
+            let ct_back =
+                Ciphertext::try_from_bytes(&ct.to_bytes(evaluator.params()), evaluator.params())
+                    .unwrap();
+            assert_eq!(ct_back, ct);
+        }
+    }
+
+    #[test]
+    fn framed_bytes_reject_invalid_input() {
+        let mut rng = thread_rng();
+        let params = BfvParameters::default(5, 1 << 4);
+        let sk = SecretKey::random_with_params(&params, &mut rng);
+        let evaluator = Evaluator::new(params);
+        let params = evaluator.params();
+        let m = params
+            .plaintext_modulus_op
+            .random_vec(params.degree, &mut rng);
+        let ct = evaluator.encrypt(
+            &sk,
+            &evaluator.plaintext_encode(&m, Encoding::default()),
+            &mut rng,
+        );
+        let bytes = ct.to_bytes(params);
+
+        // every truncation is rejected
+        for len in 0..bytes.len() {
+            assert!(matches!(
+                Ciphertext::try_from_bytes(&bytes[..len], params),
+                Err(BfvError::TruncatedBytes { .. })
+            ));
+        }
+
+        // corrupted payload
+        let mut corrupted = bytes.clone();
+        corrupted[HEADER_SIZE + 10] ^= 1;
+        assert_eq!(
+            Ciphertext::try_from_bytes(&corrupted, params),
+            Err(BfvError::ChecksumMismatch)
+        );
+
+        // length of payload overflows
+        let mut corrupted = bytes.clone();
+        corrupted[HEADER_SIZE - 8..HEADER_SIZE].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(
+            Ciphertext::try_from_bytes(&corrupted, params),
+            Err(BfvError::TruncatedBytes {
+                expected: usize::MAX,
+                found: bytes.len()
+            })
+        );
+
+        let mut corrupted = bytes.clone();
+        corrupted[0] = 0;
+        assert_eq!(
+            Ciphertext::try_from_bytes(&corrupted, params),
+            Err(BfvError::InvalidMagic)
+        );
+
+        let mut corrupted = bytes.clone();
+        corrupted[4] = 2;
+        assert_eq!(
+            Ciphertext::try_from_bytes(&corrupted, params),
+            Err(BfvError::UnsupportedVersion { version: 2 })
+        );
+
+        assert_eq!(
+            SecretKey::try_from_bytes(&bytes, params),
+            Err(BfvError::ObjectTypeMismatch {
+                expected: ObjectType::SecretKey as u8,
+                found: ObjectType::Ciphertext as u8
+            })
+        );
+
+        let mut other_params = BfvParameters::default(5, 1 << 4);
+        other_params.change_hamming_weight(params.hw / 2);
+        assert_eq!(
+            Ciphertext::try_from_bytes(&bytes, &other_params),
+            Err(BfvError::ParametersMismatch)
+        );
+
+        // malformed payloads with valid checksum
+        let mut value =
+            proto::Ciphertext::decode(&bytes[HEADER_SIZE..bytes.len() - CHECKSUM_SIZE]).unwrap();
+        value.c[0].coefficients[0].pop();
+        assert!(matches!(
+            Ciphertext::try_from_bytes(&reframe(&bytes, &value.encode_to_vec()), params),
+            Err(BfvError::InvalidObject { .. })
+        ));
+
+        value.c.clear();
+        assert_eq!(
+            Ciphertext::try_from_bytes(&reframe(&bytes, &value.encode_to_vec()), params),
+            Err(BfvError::CiphertextSizeMismatch {
+                expected: 1,
+                found: 0
+            })
+        );
+
+        assert!(matches!(
+            Ciphertext::try_from_bytes(&reframe(&bytes, &[0xff; 16]), params),
+            Err(BfvError::InvalidObject { .. })
+        ));
+
+        let mut value =
+            proto::Ciphertext::decode(&bytes[HEADER_SIZE..bytes.len() - CHECKSUM_SIZE]).unwrap();
+        value.level = params.max_level as u32 + 1;
+        assert!(matches!(
+            Ciphertext::try_from_bytes(&reframe(&bytes, &value.encode_to_vec()), params),
+            Err(BfvError::InvalidLevel { .. })
+        ));
+    }
+
+    #[test]
+    fn framed_bytes_reject_key_of_other_key_switching_method() {
+        let mut rng = thread_rng();
+        let bv_params = BfvParameters::new(&[50; 4], 65537, 1 << 4);
+        let mut hybrid_params = bv_params.clone();
+        hybrid_params.enable_hybrid_key_switching(&[50, 50, 50]);
+        let sk = SecretKey::random_with_params(&bv_params, &mut rng);
+
+        // BV key has the same shape for both parameters since Q is the same
+        let bv_ek = EvaluationKey::new(&bv_params, &sk, &[0], &[0], &[1], &mut rng);
+        let mut rtg =
+            proto::GaloisKey::try_from_with_parameters(&bv_ek.rtgs[&(1, 0)], &bv_params).unwrap();
+        rtg.fingerprint = hybrid_params.fingerprint().to_vec();
+        let bytes = encode_frame(ObjectType::GaloisKey, 0, &rtg, &hybrid_params);
+        assert!(matches!(
+            GaloisKey::try_from_bytes(&bytes, &hybrid_params),
+            Err(BfvError::InvalidObject { .. })
+        ));
+
+        let hybrid_ek = EvaluationKey::new(&hybrid_params, &sk, &[0], &[], &[], &mut rng);
+        let mut rlk = proto::RelinearizationKey::try_from_with_parameters(
+            &hybrid_ek.rlks[&0],
+            &hybrid_params,
+        )
+        .unwrap();
+        rlk.fingerprint = bv_params.fingerprint().to_vec();
+        let bytes = encode_frame(ObjectType::RelinearizationKey, 0, &rlk, &bv_params);
+        assert!(matches!(
+            RelinearizationKey::try_from_bytes(&bytes, &bv_params),
+            Err(BfvError::InvalidObject { .. })
+        ));
+    }
+}
@@ -2,57 +2,266 @@ use std::collections::HashMap;
 
 use crate::{
     convert_bytes_to_ternary, convert_from_bytes, convert_ternary_to_bytes, convert_to_bytes,
-    BfvParameters, Ciphertext, EvaluationKey, GaloisKey, HybridKeySwitchingKey, Poly, PolyContext,
-    PolyType, RelinearizationKey, Representation, SecretKey, Substitution,
+    parameters, BVKeySwitchingKey, BfvError, BfvParameters, Ciphertext, EncodingType,
+    EvaluationKey, GaloisKey, HybridKeySwitchingKey, KeySwitchingKey, KeySwitchingMethod, Poly,
+    PolyContext, PolyType, PublicKey, RelinearizationKey, Representation, SecretKey, Substitution,
 };
 use itertools::{izip, Itertools};
 use ndarray::Array2;
+use prost::Message;
 use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
-use traits::{TryFromWithParameters, TryFromWithPolyContext};
+use sha2::{Digest, Sha256};
+use traits::{Ntt, TryFromWithParameters, TryFromWithPolyContext};
 
-// include protos
+mod frame;
+pub use frame::{ObjectType, FORMAT_VERSION};
+
+// include checked-in protos. Regenerate with `build-protos` feature whenever `bfv.proto` changes
+// (see `checked_in_protos_match_generated`).
 pub mod proto {
-    include!(concat!(env!("OUT_DIR"), "/_.rs"));
+    include!("bfv.rs");
+}
+
+// Parameters //
+impl<T: Ntt> From<&parameters::BfvParameters<T>> for proto::Parameters {
+    fn from(value: &parameters::BfvParameters<T>) -> Self {
+        // special moduli kept after switching back to BV are not part of parameters
+        let (special_moduli, alpha) = match value.key_switching_method() {
+            KeySwitchingMethod::BV => (vec![], 0),
+            KeySwitchingMethod::Hybrid => (
+                value.special_moduli.clone().unwrap_or_default(),
+                value.alpha.unwrap_or_default(),
+            ),
+        };
+        proto::Parameters {
+            degree: value.degree as u32,
+            plaintext_modulus: value.plaintext_modulus,
+            ciphertext_moduli: value.ciphertext_moduli.clone(),
+            extension_moduli: value.extension_moduli.clone(),
+            special_moduli,
+            variance: value.variance as u32,
+            hw: value.hw as u32,
+            alpha: alpha as u32,
+        }
+    }
+}
+
+impl TryFrom<&proto::Parameters> for BfvParameters {
+    type Error = BfvError;
+
+    /// Returns error if moduli are invalid (see `BfvParameters::try_with_moduli`)
+    fn try_from(value: &proto::Parameters) -> Result<Self, Self::Error> {
+        let mut params = BfvParameters::try_with_moduli(
+            &value.ciphertext_moduli,
+            &value.extension_moduli,
+            &[],
+            value.plaintext_modulus,
+            value.degree as usize,
+        )?;
+        params.change_variance(value.variance as usize);
+        params.change_hamming_weight(value.hw as usize);
+        if !value.special_moduli.is_empty() {
+            params.try_enable_hybrid_key_switching_with_moduli(
+                &value.special_moduli,
+                value.alpha as usize,
+            )?;
+        }
+        Ok(params)
+    }
+}
+
+impl BfvParameters {
+    /// Returns SHA-256 digest of serialized parameters
+    pub fn fingerprint(&self) -> &[u8] {
+        &self.fingerprint
+    }
+}
+
+/// Returns SHA-256 digest of serialized parameters. Called once whenever parameters change.
+pub(crate) fn compute_fingerprint<T: Ntt>(params: &parameters::BfvParameters<T>) -> Vec<u8> {
+    let bytes = proto::Parameters::from(params).encode_to_vec();
+    Sha256::digest(bytes).to_vec()
+}
+
+impl Ciphertext {
+    /// Serializes ciphertext to bytes. If ciphertext has a valid seed then `c[1]` is
+    /// dropped and only the seed is stored, which halves the size of a fresh ciphertext.
+    /// Otherwise all polynomials are stored.
+    pub fn to_compressed_bytes(&self, params: &BfvParameters) -> Vec<u8> {
+        compressed_ciphertext_proto(self, params).encode_to_vec()
+    }
+
+    /// Deserializes ciphertext from bytes produced by [Ciphertext::to_compressed_bytes].
+    /// If bytes contain seed then `c[1]` is regenerated from it.
+    ///
+    /// Returns error if bytes are not a valid ciphertext for `params`
+    pub fn from_compressed_bytes(
+        bytes: &[u8],
+        params: &BfvParameters,
+    ) -> Result<Ciphertext, BfvError> {
+        let ct_proto = proto::Ciphertext::decode(bytes).map_err(|e| BfvError::InvalidObject {
+            reason: e.to_string(),
+        })?;
+        Ciphertext::try_from_with_parameters(&ct_proto, params)
+    }
+}
+
+/// Returns proto of ciphertext with seed in place of `c[1]` if ciphertext has a valid seed
+fn compressed_ciphertext_proto(ct: &Ciphertext, params: &BfvParameters) -> proto::Ciphertext {
+    if ct.has_valid_seed(params) {
+        proto::Ciphertext::try_from_with_parameters(ct, params).unwrap()
+    } else {
+        let mut ct = ct.clone();
+        ct.seed = None;
+        proto::Ciphertext::try_from_with_parameters(&ct, params).unwrap()
+    }
+}
+
+/// Returns error if `fingerprint` does not match fingerprint of `parameters`
+fn check_fingerprint(fingerprint: &[u8], parameters: &BfvParameters) -> Result<(), BfvError> {
+    if fingerprint != parameters.fingerprint() {
+        return Err(BfvError::ParametersMismatch);
+    }
+    Ok(())
+}
+
+/// Returns error if key switching method of `parameters` is not `method`
+fn check_key_switching_method(
+    method: KeySwitchingMethod,
+    parameters: &BfvParameters,
+) -> Result<(), BfvError> {
+    if parameters.key_switching_method() != method {
+        return Err(BfvError::InvalidObject {
+            reason: format!(
+                "{method:?} key switching key does not match {:?} key switching method of parameters",
+                parameters.key_switching_method()
+            ),
+        });
+    }
+    Ok(())
+}
+
+fn invalid(reason: impl Into<String>) -> BfvError {
+    BfvError::InvalidObject {
+        reason: reason.into(),
+    }
+}
+
+/// Returns `level` if it is a valid level of `parameters`
+fn check_level(level: u32, parameters: &BfvParameters) -> Result<usize, BfvError> {
+    let level = level as usize;
+    if level > parameters.max_level {
+        return Err(BfvError::InvalidLevel {
+            level,
+            max_level: parameters.max_level,
+        });
+    }
+    Ok(level)
+}
+
+/// Returns seed if `seed` is either missing or exactly 32 bytes
+fn check_seed(
+    seed: &Option<Vec<u8>>,
+) -> Result<Option<<ChaCha8Rng as SeedableRng>::Seed>, BfvError> {
+    seed.as_ref()
+        .map(|seed| {
+            seed.as_slice()
+                .try_into()
+                .map_err(|_| invalid(format!("seed has {} bytes instead of 32", seed.len())))
+        })
+        .transpose()
+}
+
+/// Returns error if `poly` does not have one correctly sized limb with coefficients < qi for every
+/// modulus qi in `ctx`
+fn check_poly(poly: &proto::Poly, ctx: &PolyContext<'_>) -> Result<(), BfvError> {
+    if poly.coefficients.len() != ctx.moduli_count() {
+        return Err(invalid(format!(
+            "polynomial has {} limbs instead of {}",
+            poly.coefficients.len(),
+            ctx.moduli_count()
+        )));
+    }
+
+    for (limb, modqi) in poly.coefficients.iter().zip(ctx.iter_moduli_ops()) {
+        let bits = (64 - modqi.modulus().leading_zeros()) as usize;
+        let expected = (bits * ctx.degree()).div_ceil(8);
+        if limb.len() != expected {
+            return Err(invalid(format!(
+                "polynomial limb has {} bytes instead of {expected}",
+                limb.len()
+            )));
+        }
+        if convert_from_bytes(limb, modqi.modulus())
+            .iter()
+            .any(|v| *v >= modqi.modulus())
+        {
+            return Err(invalid("polynomial coefficient exceeds modulus"));
+        }
+    }
+    Ok(())
+}
+
+/// Returns error if there are not `count` polynomials
+fn check_poly_count(polys: &[proto::Poly], count: usize) -> Result<(), BfvError> {
+    if polys.len() != count {
+        return Err(invalid(format!(
+            "expected {count} polynomials, found {}",
+            polys.len()
+        )));
+    }
+    Ok(())
 }
 
 // Poly //
 impl<'a> TryFromWithPolyContext<'a> for Poly {
     type Value = proto::Poly;
     type PolyContext = crate::PolyContext<'a>;
+    type Error = BfvError;
+
+    fn try_from_with_context(
+        poly: &Self::Value,
+        poly_ctx: &'a Self::PolyContext,
+    ) -> Result<Self, Self::Error> {
+        check_poly(poly, poly_ctx)?;
 
-    fn try_from_with_context(poly: &Self::Value, poly_ctx: &'a Self::PolyContext) -> Self {
         let coefficients = izip!(poly.coefficients.iter(), poly_ctx.iter_moduli_ops())
-            .flat_map(|(xi, modqi)| {
-                let values = convert_from_bytes(xi, modqi.modulus());
-                assert!(values.len() == poly_ctx.degree());
-                values
-            })
+            .flat_map(|(xi, modqi)| convert_from_bytes(xi, modqi.modulus()))
             .collect_vec();
         let coefficients =
             Array2::from_shape_vec((poly_ctx.moduli_count(), poly_ctx.degree()), coefficients)
-                .unwrap();
+                .map_err(|e| invalid(e.to_string()))?;
 
-        Poly {
+        Ok(Poly {
             coefficients,
             representation: Representation::Coefficient,
-        }
+        })
     }
 }
 impl<'a> TryFromWithPolyContext<'a> for proto::Poly {
     type Value = Poly;
     type PolyContext = crate::PolyContext<'a>;
-
-    fn try_from_with_context(poly: &Self::Value, poly_ctx: &'a Self::PolyContext) -> Self {
-        assert!(poly.representation == Representation::Coefficient);
+    type Error = BfvError;
+
+    fn try_from_with_context(
+        poly: &Self::Value,
+        poly_ctx: &'a Self::PolyContext,
+    ) -> Result<Self, Self::Error> {
+        if poly.representation != Representation::Coefficient {
+            return Err(BfvError::RepresentationMismatch {
+                expected: Representation::Coefficient,
+                found: poly.representation.clone(),
+            });
+        }
 
         let bytes = izip!(poly.coefficients.outer_iter(), poly_ctx.iter_moduli_ops())
             .map(|(xi, modqi)| convert_to_bytes(xi.as_slice().unwrap(), modqi.modulus()))
             .collect_vec();
 
-        proto::Poly {
+        Ok(proto::Poly {
             coefficients: bytes,
-        }
+        })
     }
 }
 
@@ -60,24 +269,124 @@ impl<'a> TryFromWithPolyContext<'a> for proto::Poly {
 impl TryFromWithParameters for proto::SecretKey {
     type Value = SecretKey;
     type Parameters = BfvParameters;
+    type Error = BfvError;
 
-    fn try_from_with_parameters(value: &Self::Value, parameters: &Self::Parameters) -> Self {
+    fn try_from_with_parameters(
+        value: &Self::Value,
+        parameters: &Self::Parameters,
+    ) -> Result<Self, Self::Error> {
         let bytes = convert_ternary_to_bytes(&value.coefficients);
-        proto::SecretKey {
+        Ok(proto::SecretKey {
             coefficients: bytes,
-        }
+            fingerprint: parameters.fingerprint().to_vec(),
+        })
     }
 }
 
 impl TryFromWithParameters for SecretKey {
     type Parameters = BfvParameters;
     type Value = proto::SecretKey;
+    type Error = BfvError;
+
+    fn try_from_with_parameters(
+        value: &Self::Value,
+        parameters: &Self::Parameters,
+    ) -> Result<Self, Self::Error> {
+        check_fingerprint(&value.fingerprint, parameters)?;
+
+        // `convert_ternary_to_bytes` may append a trailing byte
+        let min_len = parameters.degree.div_ceil(4);
+        if value.coefficients.len() < min_len || value.coefficients.len() > min_len + 1 {
+            return Err(invalid("secret key does not match degree"));
+        }
+        // 2 bit values are in {0, 1, 2}
+        if value
+            .coefficients
+            .iter()
+            .flat_map(|b| (0..4).map(move |i| (b >> (i * 2)) & 3))
+            .take(parameters.degree)
+            .any(|v| v == 3)
+        {
+            return Err(invalid("secret key coefficient is not ternary"));
+        }
 
-    fn try_from_with_parameters(value: &Self::Value, parameters: &Self::Parameters) -> Self {
         let coefficients =
             convert_bytes_to_ternary(&value.coefficients, parameters.degree).into_boxed_slice();
 
-        SecretKey { coefficients }
+        Ok(SecretKey { coefficients })
+    }
+}
+
+// PublicKey //
+impl TryFromWithParameters for proto::PublicKey {
+    type Value = PublicKey;
+    type Parameters = BfvParameters;
+    type Error = BfvError;
+
+    fn try_from_with_parameters(
+        value: &Self::Value,
+        parameters: &Self::Parameters,
+    ) -> Result<Self, Self::Error> {
+        let ctx = parameters.poly_ctx(&PolyType::Q, value.level);
+
+        // Public key polynomials are always stored in `Evaluation` form
+        let mut c0 = value.c0.clone();
+        ctx.change_representation(&mut c0, Representation::Coefficient);
+        let c0 = Some(proto::Poly::try_from_with_context(&c0, &ctx)?);
+
+        let c1 = {
+            if value.seed.is_none() {
+                let mut c1 = value.c1.clone();
+                ctx.change_representation(&mut c1, Representation::Coefficient);
+                Some(proto::Poly::try_from_with_context(&c1, &ctx)?)
+            } else {
+                None
+            }
+        };
+
+        let seed = value.seed.map(|s| s.to_vec());
+
+        Ok(proto::PublicKey {
+            c0,
+            c1,
+            seed,
+            level: value.level as u32,
+            fingerprint: parameters.fingerprint().to_vec(),
+        })
+    }
+}
+
+impl TryFromWithParameters for PublicKey {
+    type Value = proto::PublicKey;
+    type Parameters = BfvParameters;
+    type Error = BfvError;
+
+    fn try_from_with_parameters(
+        value: &Self::Value,
+        parameters: &Self::Parameters,
+    ) -> Result<Self, Self::Error> {
+        check_fingerprint(&value.fingerprint, parameters)?;
+
+        let level = check_level(value.level, parameters)?;
+        let ctx = parameters.poly_ctx(&PolyType::Q, level);
+
+        let c0 = value.c0.as_ref().ok_or_else(|| invalid("c0 missing"))?;
+        let mut c0 = Poly::try_from_with_context(c0, &ctx)?;
+        ctx.change_representation(&mut c0, Representation::Evaluation);
+
+        let (mut c1, seed) = match (&value.c1, check_seed(&value.seed)?) {
+            (Some(c1), None) => (Poly::try_from_with_context(c1, &ctx)?, None),
+            (None, Some(seed)) => (ctx.random_with_seed(seed), Some(seed)),
+            _ => return Err(invalid("public key must have exactly one of c1 or seed")),
+        };
+        ctx.change_representation(&mut c1, Representation::Evaluation);
+
+        Ok(PublicKey {
+            c0,
+            c1,
+            seed,
+            level,
+        })
     }
 }
 
@@ -85,8 +394,17 @@ impl TryFromWithParameters for SecretKey {
 impl TryFromWithParameters for proto::Ciphertext {
     type Value = Ciphertext;
     type Parameters = BfvParameters;
-    fn try_from_with_parameters(value: &Self::Value, parameters: &Self::Parameters) -> Self {
-        assert!(value.poly_type() == PolyType::Q);
+    type Error = BfvError;
+    fn try_from_with_parameters(
+        value: &Self::Value,
+        parameters: &Self::Parameters,
+    ) -> Result<Self, Self::Error> {
+        if value.poly_type() != PolyType::Q {
+            return Err(BfvError::PolyTypeMismatch {
+                expected: PolyType::Q,
+                found: value.poly_type(),
+            });
+        }
         let poly_ctx = parameters.poly_ctx(&value.poly_type, value.level);
 
         let slice = {
@@ -97,7 +415,12 @@ impl TryFromWithParameters for proto::Ciphertext {
                 // if seed is present, then the ciphertext can be assumed to be fresh ciphertext with
                 // polynomial degree of <= 2 where the second polynomial is seeded. Thus we only need to
                 // serialise the first polynomial
-                assert!(value.c.len() <= 2);
+                if value.c.len() > 2 {
+                    return Err(BfvError::CiphertextSizeMismatch {
+                        expected: 2,
+                        found: value.c.len(),
+                    });
+                }
                 1
             }
         };
@@ -106,53 +429,81 @@ impl TryFromWithParameters for proto::Ciphertext {
             .iter()
             .map(|p| {
                 // Avoid converting polynomial to `Coefficient` representation to allow
-                // assert in `Poly` to fail. This also avoids adding silent NTTs of which
+                // conversion of `Poly` to fail. This also avoids adding silent NTTs of which
                 // user of the API isn't aware.
                 proto::Poly::try_from_with_context(p, &poly_ctx)
             })
-            .collect_vec();
+            .collect::<Result<Vec<_>, _>>()?;
 
-        let seed = value.seed.as_ref().and_then(|s| Some(s.to_vec()));
+        let seed = value.seed.as_ref().map(|s| s.to_vec());
 
-        proto::Ciphertext {
+        let encoding_type = match value.encoding_type {
+            EncodingType::Simd => proto::EncodingType::Simd,
+            EncodingType::Poly => proto::EncodingType::Poly,
+        };
+
+        Ok(proto::Ciphertext {
             c,
             level: value.level as u32,
             seed,
-        }
+            fingerprint: parameters.fingerprint().to_vec(),
+            encoding_type: encoding_type as i32,
+        })
     }
 }
 impl TryFromWithParameters for Ciphertext {
     type Value = proto::Ciphertext;
     type Parameters = BfvParameters;
-    fn try_from_with_parameters(value: &Self::Value, parameters: &Self::Parameters) -> Self {
-        let level = value.level as usize;
+    type Error = BfvError;
+    fn try_from_with_parameters(
+        value: &Self::Value,
+        parameters: &Self::Parameters,
+    ) -> Result<Self, Self::Error> {
+        check_fingerprint(&value.fingerprint, parameters)?;
+
+        let level = check_level(value.level, parameters)?;
         let poly_ctx = parameters.poly_ctx(&PolyType::Q, level);
 
+        let seed = check_seed(&value.seed)?;
+        // seeded ciphertext stores only c[0]
+        let expected = if seed.is_some() { 1 } else { 2 };
+        if (seed.is_some() && value.c.len() != 1) || value.c.len() < expected {
+            return Err(BfvError::CiphertextSizeMismatch {
+                expected,
+                found: value.c.len(),
+            });
+        }
+
+        let encoding_type = match proto::EncodingType::from_i32(value.encoding_type) {
+            Some(proto::EncodingType::Simd) => EncodingType::Simd,
+            Some(proto::EncodingType::Poly) => EncodingType::Poly,
+            None => {
+                return Err(invalid(format!(
+                    "unknown encoding type {}",
+                    value.encoding_type
+                )))
+            }
+        };
+
         let mut c = value
             .c
             .iter()
             .map(|p_proto| Poly::try_from_with_context(p_proto, &poly_ctx))
-            .collect_vec();
+            .collect::<Result<Vec<_>, _>>()?;
 
-        let seed = value.seed.as_ref().and_then(|s| {
-            let mut seed = <ChaCha8Rng as SeedableRng>::Seed::default();
-            seed.copy_from_slice(&s);
-            Some(seed)
-        });
-
-        if seed.is_some() {
-            assert!(c.len() < 2);
-
-            let a = poly_ctx.random_with_seed(seed.unwrap());
+        if let Some(seed) = seed {
+            let a = poly_ctx.random_with_seed(seed);
             c.push(a);
         }
 
-        Ciphertext {
+        Ok(Ciphertext {
             c,
             poly_type: PolyType::Q,
             level,
             seed,
-        }
+            encoding_type,
+            noise: None,
+        })
     }
 }
 
@@ -160,7 +511,11 @@ impl TryFromWithParameters for Ciphertext {
 impl<'a> TryFromWithPolyContext<'a> for proto::HybridKeySwitchingKey {
     type PolyContext = PolyContext<'a>;
     type Value = HybridKeySwitchingKey;
-    fn try_from_with_context(value: &Self::Value, poly_ctx: &'a Self::PolyContext) -> Self {
+    type Error = BfvError;
+    fn try_from_with_context(
+        value: &Self::Value,
+        poly_ctx: &'a Self::PolyContext,
+    ) -> Result<Self, Self::Error> {
         let c0s = value
             .c0s
             .iter()
@@ -171,7 +526,7 @@ impl<'a> TryFromWithPolyContext<'a> for proto::HybridKeySwitchingKey {
                 poly_ctx.change_representation(&mut p, Representation::Coefficient);
                 proto::Poly::try_from_with_context(&p, &poly_ctx)
             })
-            .collect_vec();
+            .collect::<Result<Vec<_>, _>>()?;
 
         let c1s = {
             if value.seed.is_none() {
@@ -183,50 +538,53 @@ impl<'a> TryFromWithPolyContext<'a> for proto::HybridKeySwitchingKey {
                         poly_ctx.change_representation(&mut p, Representation::Coefficient);
                         proto::Poly::try_from_with_context(&p, &poly_ctx)
                     })
-                    .collect_vec()
+                    .collect::<Result<Vec<_>, _>>()?
             } else {
                 vec![]
             }
         };
 
-        let seed = value.seed.and_then(|s| Some(s.to_vec()));
+        let seed = value.seed.map(|s| s.to_vec());
 
-        proto::HybridKeySwitchingKey { c0s, c1s, seed }
+        Ok(proto::HybridKeySwitchingKey { c0s, c1s, seed })
     }
 }
 
 impl<'a> TryFromWithPolyContext<'a> for HybridKeySwitchingKey {
     type PolyContext = PolyContext<'a>;
     type Value = proto::HybridKeySwitchingKey;
-    fn try_from_with_context(value: &Self::Value, poly_ctx: &'a Self::PolyContext) -> Self {
+    type Error = BfvError;
+    fn try_from_with_context(
+        value: &Self::Value,
+        poly_ctx: &'a Self::PolyContext,
+    ) -> Result<Self, Self::Error> {
+        // c0s and c1s are only needed in `Evaluation` form so it safe to convert them
+        // from `Coefficient` (default form for serialization) to `Evaluation`.
+        let from_proto = |p: &proto::Poly| {
+            let mut p = Poly::try_from_with_context(p, poly_ctx)?;
+            poly_ctx.change_representation(&mut p, Representation::Evaluation);
+            Ok::<_, BfvError>(p)
+        };
+
         let c0s = value
             .c0s
             .iter()
-            .map(|p| {
-                // c0s and c1s are only needed in `Evaluation` form so it safe to convert them
-                // from `Coefficient` (default form for serialization) to `Evaluation`.
-                let mut p = Poly::try_from_with_context(p, &poly_ctx);
-                poly_ctx.change_representation(&mut p, Representation::Evaluation);
-                p
-            })
-            .collect_vec();
-
-        let (c1s, seed) = {
-            if value.seed.is_none() {
-                assert!(value.c1s.len() == value.c0s.len());
-                let c = value
-                    .c1s
-                    .iter()
-                    .map(|p| {
-                        let mut p = Poly::try_from_with_context(p, &poly_ctx);
-                        poly_ctx.change_representation(&mut p, Representation::Evaluation);
-                        p
-                    })
-                    .collect_vec();
-                (c, None)
-            } else {
-                let mut seed = <ChaCha8Rng as SeedableRng>::Seed::default();
-                seed.copy_from_slice(value.seed());
+            .map(from_proto)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (c1s, seed) = match check_seed(&value.seed)? {
+            None => {
+                check_poly_count(&value.c1s, c0s.len())?;
+                (
+                    value
+                        .c1s
+                        .iter()
+                        .map(from_proto)
+                        .collect::<Result<Vec<_>, _>>()?,
+                    None,
+                )
+            }
+            Some(seed) => {
                 // `generate_c1` returns c1s in `Coefficient` representation. Convert them to `Evaluation` representation.
                 let mut c = HybridKeySwitchingKey::generate_c1(c0s.len(), poly_ctx, seed);
                 c.iter_mut().for_each(|p| {
@@ -236,11 +594,169 @@ impl<'a> TryFromWithPolyContext<'a> for HybridKeySwitchingKey {
             }
         };
 
-        HybridKeySwitchingKey {
+        Ok(HybridKeySwitchingKey {
             seed,
             c0s: c0s.into_boxed_slice(),
             c1s: c1s.into_boxed_slice(),
+        })
+    }
+}
+
+// BV Key Switching Key //
+impl<'a> TryFromWithPolyContext<'a> for proto::BvKeySwitchingKey {
+    type PolyContext = PolyContext<'a>;
+    type Value = BVKeySwitchingKey;
+    type Error = BfvError;
+    fn try_from_with_context(
+        value: &Self::Value,
+        poly_ctx: &'a Self::PolyContext,
+    ) -> Result<Self, Self::Error> {
+        // c0s and c1s are always in `Evaluation` form
+        let to_proto = |p: &Poly| {
+            let mut p = p.clone();
+            poly_ctx.change_representation(&mut p, Representation::Coefficient);
+            proto::Poly::try_from_with_context(&p, poly_ctx)
+        };
+
+        let c0s = value
+            .c0s
+            .iter()
+            .map(to_proto)
+            .collect::<Result<Vec<_>, _>>()?;
+        let c1s = {
+            if value.seed.is_none() {
+                value
+                    .c1s
+                    .iter()
+                    .map(to_proto)
+                    .collect::<Result<Vec<_>, _>>()?
+            } else {
+                vec![]
+            }
+        };
+
+        let seed = value.seed.map(|s| s.to_vec());
+
+        Ok(proto::BvKeySwitchingKey { c0s, c1s, seed })
+    }
+}
+
+impl<'a> TryFromWithPolyContext<'a> for BVKeySwitchingKey {
+    type PolyContext = PolyContext<'a>;
+    type Value = proto::BvKeySwitchingKey;
+    type Error = BfvError;
+    fn try_from_with_context(
+        value: &Self::Value,
+        poly_ctx: &'a Self::PolyContext,
+    ) -> Result<Self, Self::Error> {
+        let from_proto = |p: &proto::Poly| {
+            let mut p = Poly::try_from_with_context(p, poly_ctx)?;
+            poly_ctx.change_representation(&mut p, Representation::Evaluation);
+            Ok::<_, BfvError>(p)
+        };
+
+        // BV key has one c0 (and c1) per modulus qi
+        check_poly_count(&value.c0s, poly_ctx.moduli_count())?;
+        let c0s = value
+            .c0s
+            .iter()
+            .map(from_proto)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (c1s, seed) = match check_seed(&value.seed)? {
+            None => {
+                check_poly_count(&value.c1s, c0s.len())?;
+                (
+                    value
+                        .c1s
+                        .iter()
+                        .map(from_proto)
+                        .collect::<Result<Vec<_>, _>>()?,
+                    None,
+                )
+            }
+            // `generate_c1` returns c1s in `Evaluation` representation
+            Some(seed) => (BVKeySwitchingKey::generate_c1(poly_ctx, seed), Some(seed)),
+        };
+
+        Ok(BVKeySwitchingKey {
+            c0s: c0s.into_boxed_slice(),
+            c1s: c1s.into_boxed_slice(),
+            seed,
+        })
+    }
+}
+
+// Key Switching Key //
+fn key_switching_key_to_proto(
+    ksk: &KeySwitchingKey,
+    parameters: &BfvParameters,
+    level: usize,
+) -> Result<proto::key_switching_key::Ksk, BfvError> {
+    match ksk {
+        KeySwitchingKey::BV(ksk) => {
+            let ctx = parameters.poly_ctx(&PolyType::Q, level);
+            Ok(proto::key_switching_key::Ksk::BvKsk(
+                proto::BvKeySwitchingKey::try_from_with_context(ksk, &ctx)?,
+            ))
+        }
+        KeySwitchingKey::Hybrid(ksk) => {
+            let ctx = parameters.poly_ctx(&PolyType::QP, level);
+            Ok(proto::key_switching_key::Ksk::HybridKsk(
+                proto::HybridKeySwitchingKey::try_from_with_context(ksk, &ctx)?,
+            ))
+        }
+    }
+}
+
+fn bv_key_switching_key_from_proto(
+    ksk: &proto::BvKeySwitchingKey,
+    parameters: &BfvParameters,
+    level: usize,
+) -> Result<KeySwitchingKey, BfvError> {
+    check_key_switching_method(KeySwitchingMethod::BV, parameters)?;
+    let ctx = parameters.poly_ctx(&PolyType::Q, level);
+    Ok(KeySwitchingKey::BV(
+        BVKeySwitchingKey::try_from_with_context(ksk, &ctx)?,
+    ))
+}
+
+fn hybrid_key_switching_key_from_proto(
+    ksk: &proto::HybridKeySwitchingKey,
+    parameters: &BfvParameters,
+    level: usize,
+) -> Result<KeySwitchingKey, BfvError> {
+    check_key_switching_method(KeySwitchingMethod::Hybrid, parameters)?;
+    let ksk_params = parameters
+        .hybrid_ksk_parameters
+        .as_ref()
+        .and_then(|p| p.get(level))
+        .ok_or_else(|| {
+            invalid(format!(
+                "hybrid key switching is not enabled at level {level}"
+            ))
+        })?;
+    // hybrid key has one c0 (and c1) per digit of decomposition
+    check_poly_count(&ksk.c0s, ksk_params.dnum)?;
+    let ctx = parameters.poly_ctx(&PolyType::QP, level);
+    Ok(KeySwitchingKey::Hybrid(
+        HybridKeySwitchingKey::try_from_with_context(ksk, &ctx)?,
+    ))
+}
+
+fn key_switching_key_from_proto(
+    ksk: &Option<proto::key_switching_key::Ksk>,
+    parameters: &BfvParameters,
+    level: usize,
+) -> Result<KeySwitchingKey, BfvError> {
+    match ksk {
+        Some(proto::key_switching_key::Ksk::BvKsk(ksk)) => {
+            bv_key_switching_key_from_proto(ksk, parameters, level)
         }
+        Some(proto::key_switching_key::Ksk::HybridKsk(ksk)) => {
+            hybrid_key_switching_key_from_proto(ksk, parameters, level)
+        }
+        None => Err(invalid("key switching key missing")),
     }
 }
 
@@ -248,36 +764,61 @@ impl<'a> TryFromWithPolyContext<'a> for HybridKeySwitchingKey {
 impl TryFromWithParameters for proto::GaloisKey {
     type Parameters = BfvParameters;
     type Value = GaloisKey;
+    type Error = BfvError;
+
+    fn try_from_with_parameters(
+        value: &Self::Value,
+        parameters: &Self::Parameters,
+    ) -> Result<Self, Self::Error> {
+        let ksk = match key_switching_key_to_proto(&value.ksk_key, parameters, value.level)? {
+            proto::key_switching_key::Ksk::BvKsk(ksk) => proto::galois_key::Ksk::BvKsk(ksk),
+            proto::key_switching_key::Ksk::HybridKsk(ksk) => proto::galois_key::Ksk::HybridKsk(ksk),
+        };
 
-    fn try_from_with_parameters(value: &Self::Value, parameters: &Self::Parameters) -> Self {
-        let ctx = parameters.poly_ctx(&PolyType::QP, value.level);
-        let ksk = Some(proto::HybridKeySwitchingKey::try_from_with_context(
-            &value.ksk_key,
-            &ctx,
-        ));
-        proto::GaloisKey {
+        Ok(proto::GaloisKey {
             exponent: value.substitution.exponent as u32,
-            ksk,
+            ksk: Some(ksk),
             level: value.level as u32,
-        }
+            fingerprint: parameters.fingerprint().to_vec(),
+        })
     }
 }
 
 impl TryFromWithParameters for GaloisKey {
     type Value = proto::GaloisKey;
     type Parameters = BfvParameters;
-
-    fn try_from_with_parameters(value: &Self::Value, parameters: &Self::Parameters) -> Self {
+    type Error = BfvError;
+
+    fn try_from_with_parameters(
+        value: &Self::Value,
+        parameters: &Self::Parameters,
+    ) -> Result<Self, Self::Error> {
+        check_fingerprint(&value.fingerprint, parameters)?;
+
+        let level = check_level(value.level, parameters)?;
+        if value.exponent & 1 != 1 || value.exponent as usize >= 2 * parameters.degree {
+            return Err(invalid(format!(
+                "invalid galois exponent {}",
+                value.exponent
+            )));
+        }
         let substitution = Substitution::new(value.exponent as usize, parameters.degree);
-        let level = value.level as usize;
 
-        let ctx = parameters.poly_ctx(&PolyType::QP, level);
-        let ksk = HybridKeySwitchingKey::try_from_with_context(&value.ksk.as_ref().unwrap(), &ctx);
-        GaloisKey {
+        let ksk = match &value.ksk {
+            Some(proto::galois_key::Ksk::BvKsk(ksk)) => {
+                bv_key_switching_key_from_proto(ksk, parameters, level)?
+            }
+            Some(proto::galois_key::Ksk::HybridKsk(ksk)) => {
+                hybrid_key_switching_key_from_proto(ksk, parameters, level)?
+            }
+            None => return Err(invalid("galois key missing")),
+        };
+
+        Ok(GaloisKey {
             substitution,
             ksk_key: ksk,
             level,
-        }
+        })
     }
 }
 
@@ -285,36 +826,72 @@ impl TryFromWithParameters for GaloisKey {
 impl TryFromWithParameters for proto::RelinearizationKey {
     type Parameters = BfvParameters;
     type Value = RelinearizationKey;
-    fn try_from_with_parameters(value: &Self::Value, parameters: &Self::Parameters) -> Self {
+    type Error = BfvError;
+    fn try_from_with_parameters(
+        value: &Self::Value,
+        parameters: &Self::Parameters,
+    ) -> Result<Self, Self::Error> {
         let level = value.level;
-        let ctx = parameters.poly_ctx(&PolyType::QP, level);
 
         // message types default to optional in proto3. For more info check this
         // answer https://github.com/tokio-rs/prost/discussions/679 and the one linked in it.
         // This is enforced by proto3, not something prost does.
-        let ksk = Some(proto::HybridKeySwitchingKey::try_from_with_context(
-            &value.ksk, &ctx,
-        ));
+        let ksk = match key_switching_key_to_proto(&value.ksks[0], parameters, level)? {
+            proto::key_switching_key::Ksk::BvKsk(ksk) => {
+                proto::relinearization_key::Ksk::BvKsk(ksk)
+            }
+            proto::key_switching_key::Ksk::HybridKsk(ksk) => {
+                proto::relinearization_key::Ksk::HybridKsk(ksk)
+            }
+        };
 
-        proto::RelinearizationKey {
-            ksk,
+        let higher_ksks = value
+            .ksks
+            .iter()
+            .skip(1)
+            .map(|ksk| {
+                Ok(proto::KeySwitchingKey {
+                    ksk: Some(key_switching_key_to_proto(ksk, parameters, level)?),
+                })
+            })
+            .collect::<Result<Vec<_>, BfvError>>()?;
+
+        Ok(proto::RelinearizationKey {
+            ksk: Some(ksk),
             level: level as u32,
-        }
+            fingerprint: parameters.fingerprint().to_vec(),
+            higher_ksks,
+        })
     }
 }
 
 impl TryFromWithParameters for RelinearizationKey {
     type Parameters = BfvParameters;
     type Value = proto::RelinearizationKey;
-    fn try_from_with_parameters(value: &Self::Value, parameters: &Self::Parameters) -> Self {
-        let level = value.level as usize;
-        let ctx = parameters.poly_ctx(&PolyType::QP, level);
-        let ksk = HybridKeySwitchingKey::try_from_with_context(
-            value.ksk.as_ref().expect("Rlk missing"),
-            &ctx,
-        );
+    type Error = BfvError;
+    fn try_from_with_parameters(
+        value: &Self::Value,
+        parameters: &Self::Parameters,
+    ) -> Result<Self, Self::Error> {
+        check_fingerprint(&value.fingerprint, parameters)?;
+
+        let level = check_level(value.level, parameters)?;
+        let ksk = match &value.ksk {
+            Some(proto::relinearization_key::Ksk::BvKsk(ksk)) => {
+                bv_key_switching_key_from_proto(ksk, parameters, level)?
+            }
+            Some(proto::relinearization_key::Ksk::HybridKsk(ksk)) => {
+                hybrid_key_switching_key_from_proto(ksk, parameters, level)?
+            }
+            None => return Err(invalid("relinearization key missing")),
+        };
+
+        let mut ksks = vec![ksk];
+        for ksk in value.higher_ksks.iter() {
+            ksks.push(key_switching_key_from_proto(&ksk.ksk, parameters, level)?);
+        }
 
-        RelinearizationKey { ksk, level }
+        Ok(RelinearizationKey { ksks, level })
     }
 }
 
@@ -322,14 +899,18 @@ impl TryFromWithParameters for RelinearizationKey {
 impl TryFromWithParameters for proto::EvaluationKey {
     type Parameters = BfvParameters;
     type Value = EvaluationKey;
-    fn try_from_with_parameters(value: &Self::Value, parameters: &Self::Parameters) -> Self {
+    type Error = BfvError;
+    fn try_from_with_parameters(
+        value: &Self::Value,
+        parameters: &Self::Parameters,
+    ) -> Result<Self, Self::Error> {
         // since HashMap iterates over values in arbitrary order seralisation of same `EvaluationKey`
         // twice can produce different `proto::EvaluationKey`s.
         let rlks = value
             .rlks
             .iter()
             .map(|(i, k)| proto::RelinearizationKey::try_from_with_parameters(&k, parameters))
-            .collect_vec();
+            .collect::<Result<Vec<_>, _>>()?;
         let mut rot_indices = vec![];
         let rtgs = value
             .rtgs
@@ -338,47 +919,119 @@ impl TryFromWithParameters for proto::EvaluationKey {
                 rot_indices.push(i.0 as i32);
                 proto::GaloisKey::try_from_with_parameters(&k, parameters)
             })
-            .collect_vec();
+            .collect::<Result<Vec<_>, _>>()?;
 
-        proto::EvaluationKey {
+        Ok(proto::EvaluationKey {
             rlks,
             rtgs,
             rot_indices,
-        }
+            fingerprint: parameters.fingerprint().to_vec(),
+        })
     }
 }
 
 impl TryFromWithParameters for EvaluationKey {
     type Parameters = BfvParameters;
     type Value = proto::EvaluationKey;
+    type Error = BfvError;
+
+    fn try_from_with_parameters(
+        value: &Self::Value,
+        parameters: &Self::Parameters,
+    ) -> Result<Self, Self::Error> {
+        check_fingerprint(&value.fingerprint, parameters)?;
+
+        if value.rot_indices.len() != value.rtgs.len() {
+            return Err(invalid(
+                "no. of rotation indices does not match no. of galois keys",
+            ));
+        }
 
-    fn try_from_with_parameters(value: &Self::Value, parameters: &Self::Parameters) -> Self {
         let mut rlks = HashMap::new();
-        value.rlks.iter().for_each(|v| {
-            let v = RelinearizationKey::try_from_with_parameters(v, parameters);
+        for v in value.rlks.iter() {
+            let v = RelinearizationKey::try_from_with_parameters(v, parameters)?;
             rlks.insert(v.level, v);
-        });
+        }
 
         let mut rtgs = HashMap::new();
-        value
-            .rtgs
-            .iter()
-            .zip(value.rot_indices.iter())
-            .for_each(|(gk, rot_index)| {
-                let v = GaloisKey::try_from_with_parameters(gk, parameters);
-                rtgs.insert((*rot_index as isize, v.level), v);
-            });
+        for (gk, rot_index) in value.rtgs.iter().zip(value.rot_indices.iter()) {
+            let v = GaloisKey::try_from_with_parameters(gk, parameters)?;
+            rtgs.insert((*rot_index as isize, v.level), v);
+        }
 
-        EvaluationKey { rlks, rtgs }
+        Ok(EvaluationKey { rlks, rtgs })
     }
 }
 
 mod tests {
     use super::*;
     use crate::{Encoding, Evaluator, SecretKey};
-    use prost::Message;
     use rand::thread_rng;
 
+    #[test]
+    fn checked_in_protos_match_proto_file() {
+        let hash = Sha256::digest(include_bytes!("bfv.proto"));
+        assert!(
+            include_str!("bfv.rs").contains(&format!("// bfv.proto sha256: {hash:x}\n")),
+            "bfv.rs is stale. Regenerate with `build-protos` feature (see `checked_in_protos_match_generated`)"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "build-protos")]
+    fn checked_in_protos_match_generated() {
+        let generated = concat!(env!("OUT_DIR"), "/bfv.rs");
+        assert!(
+            include_str!("bfv.rs") == include_str!(concat!(env!("OUT_DIR"), "/bfv.rs")),
+            "bfv.rs does not match generated bindings. Copy {generated} to src/proto/bfv.rs"
+        );
+    }
+
+    #[test]
+    fn serialize_and_deserialize_parameters() {
+        let params = BfvParameters::default(5, 1 << 4);
+
+        let params_proto = proto::Parameters::from(&params);
+        let params_back = BfvParameters::try_from(
+            &proto::Parameters::decode(params_proto.encode_to_vec().as_slice()).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(params, params_back);
+        assert_eq!(params.fingerprint(), params_back.fingerprint());
+        // non-default alpha
+        let mut params = BfvParameters::new(&[50; 5], 65537, 1 << 4);
+        params.enable_hybrid_key_switching_with_alpha(&[50, 50], 2);
+        let params_back = BfvParameters::try_from(&proto::Parameters::from(&params)).unwrap();
+        assert_eq!(params, params_back);
+
+        // invalid moduli
+        let mut params_proto = proto::Parameters::from(&params);
+        params_proto.ciphertext_moduli[1] = params_proto.ciphertext_moduli[0];
+        assert!(BfvParameters::try_from(&params_proto).is_err());
+        let mut params_proto = proto::Parameters::from(&params);
+        params_proto.degree = 17;
+        assert_eq!(
+            BfvParameters::try_from(&params_proto),
+            Err(BfvError::InvalidDegree { degree: 17 })
+        );
+    }
+
+    #[test]
+    fn deserialize_rejects_different_parameters() {
+        let mut rng = thread_rng();
+        let params = BfvParameters::default(5, 1 << 4);
+        let mut other_params = BfvParameters::default(5, 1 << 4);
+        other_params.change_hamming_weight(params.hw / 2);
+
+        let sk = SecretKey::random_with_params(&params, &mut rng);
+        let sk_proto = proto::SecretKey::try_from_with_parameters(&sk, &params).unwrap();
+        assert_eq!(
+            SecretKey::try_from_with_parameters(&sk_proto, &other_params),
+            Err(BfvError::ParametersMismatch)
+        );
+    }
+
     #[test]
     fn serialize_and_deserialize_secret_key() {
         let mut rng = thread_rng();
@@ -386,12 +1039,35 @@ mod tests {
 
         let sk = SecretKey::random_with_params(&params, &mut rng);
 
-        let sk_proto = proto::SecretKey::try_from_with_parameters(&sk, &params);
-        let sk_back = SecretKey::try_from_with_parameters(&sk_proto, &params);
+        let sk_proto = proto::SecretKey::try_from_with_parameters(&sk, &params).unwrap();
+        let sk_back = SecretKey::try_from_with_parameters(&sk_proto, &params).unwrap();
 
         assert_eq!(sk, sk_back);
     }
 
+    #[test]
+    fn serialize_and_deserialize_public_key() {
+        let mut rng = thread_rng();
+        let params = BfvParameters::default(5, 1 << 4);
+
+        let sk = SecretKey::random_with_params(&params, &mut rng);
+        let pk = PublicKey::new(&params, &sk, 0, &mut rng);
+
+        let pk_proto = proto::PublicKey::try_from_with_parameters(&pk, &params).unwrap();
+        let pk_back = PublicKey::try_from_with_parameters(&pk_proto, &params).unwrap();
+
+        assert_eq!(pk, pk_back);
+
+        // seeded public key is stored without c1
+        assert!(pk.seed.is_some());
+        let mut pk_full = pk.clone();
+        pk_full.seed = None;
+        let pk_full_proto = proto::PublicKey::try_from_with_parameters(&pk_full, &params).unwrap();
+        assert!(pk_proto.encode_to_vec().len() < pk_full_proto.encode_to_vec().len());
+        let pk_back = PublicKey::try_from_with_parameters(&pk_full_proto, &params).unwrap();
+        assert_eq!(pk_full, pk_back);
+    }
+
     #[test]
     fn serialize_and_deserialize_ciphertexts() {
         let mut rng = thread_rng();
@@ -406,10 +1082,112 @@ mod tests {
         let mut ct0 = evaluator.encrypt(&sk, &pt0, &mut rng);
         ct0.seed = None;
 
-        let ct_proto = proto::Ciphertext::try_from_with_parameters(&ct0, evaluator.params());
-        let ct_back = Ciphertext::try_from_with_parameters(&ct_proto, evaluator.params());
+        let ct_proto =
+            proto::Ciphertext::try_from_with_parameters(&ct0, evaluator.params()).unwrap();
+        let ct_back = Ciphertext::try_from_with_parameters(&ct_proto, evaluator.params()).unwrap();
 
         assert_eq!(ct0, ct_back);
+
+        // coefficient encoding is preserved
+        let pt1 = evaluator.plaintext_encode(&m0, Encoding::poly(0, crate::PolyCache::None));
+        let ct1 = evaluator.encrypt(&sk, &pt1, &mut rng);
+        let ct_proto =
+            proto::Ciphertext::try_from_with_parameters(&ct1, evaluator.params()).unwrap();
+        let ct_back = Ciphertext::try_from_with_parameters(
+            &proto::Ciphertext::decode(ct_proto.encode_to_vec().as_slice()).unwrap(),
+            evaluator.params(),
+        )
+        .unwrap();
+        assert_eq!(ct1, ct_back);
+        assert_eq!(ct_back.encoding_type(), EncodingType::Poly);
+    }
+
+    #[test]
+    fn compressed_ciphertext_bytes() {
+        let mut rng = thread_rng();
+        let params = BfvParameters::default(5, 1 << 4);
+
+        let sk = SecretKey::random(params.degree, params.hw, &mut rng);
+        let m0 = params
+            .plaintext_modulus_op
+            .random_vec(params.degree, &mut rng);
+        let evaluator = Evaluator::new(params);
+        let params = evaluator.params();
+        let pt0 = evaluator.plaintext_encode(&m0, Encoding::default());
+
+        // fresh ciphertext is stored with seed in place of c[1]
+        let ct0 = evaluator.encrypt(&sk, &pt0, &mut rng);
+        assert!(ct0.has_valid_seed(params));
+        let mut ct_full = ct0.clone();
+        ct_full.seed = None;
+        let compressed = ct0.to_compressed_bytes(params);
+        let uncompressed = ct_full.to_compressed_bytes(params);
+        assert!(compressed.len() < uncompressed.len() / 2 + 64);
+        assert_eq!(
+            Ciphertext::from_compressed_bytes(&compressed, params).unwrap(),
+            ct0
+        );
+        assert_eq!(
+            Ciphertext::from_compressed_bytes(&uncompressed, params).unwrap(),
+            ct_full
+        );
+
+        // adding plaintext does not modify c[1] and preserves seed
+        let pt1 = evaluator.plaintext_encode(
+            &m0,
+            Encoding::simd(0, crate::PolyCache::AddSub(Representation::Coefficient)),
+        );
+        let ct1 = evaluator.add_plaintext(&ct0, &pt1);
+        assert!(ct1.has_valid_seed(params));
+        let ct1_back =
+            Ciphertext::from_compressed_bytes(&ct1.to_compressed_bytes(params), params).unwrap();
+        assert_eq!(ct1_back, ct1);
+        assert_eq!(
+            evaluator.plaintext_decode(&evaluator.decrypt(&sk, &ct1_back), Encoding::default()),
+            evaluator.plaintext_decode(&evaluator.decrypt(&sk, &ct1), Encoding::default())
+        );
+
+        // operations that modify c[1] must not keep seed
+        let mut modified = vec![
+            evaluator.mul_scalar(&ct0, 3),
+            evaluator.negate(&ct0),
+            evaluator.add(&ct0, &ct1),
+        ];
+        let mut ct2 = ct0.clone();
+        evaluator.mod_down_next(&mut ct2);
+        modified.push(ct2);
+        let mut ct3 = ct0.clone();
+        ct3.c_ref_mut();
+        modified.push(ct3);
+        for ct in modified.iter() {
+            assert!(ct.seed.is_none());
+            let bytes = ct.to_compressed_bytes(params);
+            assert!(bytes.len() > compressed.len());
+            let ct_back = Ciphertext::from_compressed_bytes(&bytes, params).unwrap();
+            assert_eq!(&ct_back, ct);
+        }
+
+        // stale seed is detected and ciphertext is stored in full
+        let mut ct4 = ct0.clone();
+        let ctx = params.poly_ctx(&PolyType::Q, 0);
+        ctx.add_assign(&mut ct4.c[1], &ct0.c[0]);
+        assert!(ct4.seed.is_some());
+        assert!(!ct4.has_valid_seed(params));
+        let ct4_back =
+            Ciphertext::from_compressed_bytes(&ct4.to_compressed_bytes(params), params).unwrap();
+        assert_eq!(ct4_back.c, ct4.c);
+        assert!(ct4_back.seed.is_none());
+
+        // invalid bytes are rejected
+        assert!(matches!(
+            Ciphertext::from_compressed_bytes(&compressed[..compressed.len() / 2], params),
+            Err(BfvError::InvalidObject { .. })
+        ));
+        let other_params = BfvParameters::default(4, 1 << 4);
+        assert_eq!(
+            Ciphertext::from_compressed_bytes(&compressed, &other_params),
+            Err(BfvError::ParametersMismatch)
+        );
     }
 
     #[test]
@@ -419,10 +1197,10 @@ mod tests {
 
         let mut rng = thread_rng();
         let poly = ctx.random(Representation::Coefficient, &mut rng);
-        let proto = proto::Poly::try_from_with_context(&poly, &ctx);
+        let proto = proto::Poly::try_from_with_context(&poly, &ctx).unwrap();
         let bytes = proto.encode_to_vec();
         dbg!(bytes.len());
-        let poly_back = Poly::try_from_with_context(&proto, &ctx);
+        let poly_back = Poly::try_from_with_context(&proto, &ctx).unwrap();
 
         assert_eq!(poly, poly_back);
     }
@@ -445,9 +1223,9 @@ mod tests {
             &mut rng,
         );
 
-        let ksk_proto = proto::HybridKeySwitchingKey::try_from_with_context(&ksk, &qp_ctx);
+        let ksk_proto = proto::HybridKeySwitchingKey::try_from_with_context(&ksk, &qp_ctx).unwrap();
         dbg!(ksk_proto.encode_to_vec().len());
-        let ksk_back = HybridKeySwitchingKey::try_from_with_context(&ksk_proto, &qp_ctx);
+        let ksk_back = HybridKeySwitchingKey::try_from_with_context(&ksk_proto, &qp_ctx).unwrap();
 
         assert_eq!(ksk, ksk_back);
     }
@@ -461,8 +1239,8 @@ mod tests {
 
         let rlk = RelinearizationKey::new(&params, &sk, 0, &mut rng);
 
-        let rlk_proto = proto::RelinearizationKey::try_from_with_parameters(&rlk, &params);
-        let rlk_back = RelinearizationKey::try_from_with_parameters(&rlk_proto, &params);
+        let rlk_proto = proto::RelinearizationKey::try_from_with_parameters(&rlk, &params).unwrap();
+        let rlk_back = RelinearizationKey::try_from_with_parameters(&rlk_proto, &params).unwrap();
 
         assert_eq!(rlk, rlk_back);
     }
@@ -483,9 +1261,150 @@ mod tests {
             &mut rng,
         );
 
-        let ek_proto = proto::EvaluationKey::try_from_with_parameters(&ek, &params);
-        let ek_back = EvaluationKey::try_from_with_parameters(&ek_proto, &params);
+        let ek_proto = proto::EvaluationKey::try_from_with_parameters(&ek, &params).unwrap();
+        let ek_back = EvaluationKey::try_from_with_parameters(&ek_proto, &params).unwrap();
 
         assert_eq!(ek, ek_back);
     }
+
+    #[test]
+    fn serialize_and_deserialize_bv_ek() {
+        let mut rng = thread_rng();
+        let params = BfvParameters::new(&[50; 4], 65537, 1 << 4);
+
+        let sk = SecretKey::random(params.degree, params.hw, &mut rng);
+
+        let ek = EvaluationKey::new_with_rlk_max_degree(
+            &params,
+            &sk,
+            &[0, 1],
+            3,
+            &[0, 1],
+            &[1, -1],
+            &mut rng,
+        );
+
+        let ek_proto = proto::EvaluationKey::try_from_with_parameters(&ek, &params).unwrap();
+        let ek_back = EvaluationKey::try_from_with_parameters(
+            &proto::EvaluationKey::decode(ek_proto.encode_to_vec().as_slice()).unwrap(),
+            &params,
+        )
+        .unwrap();
+
+        assert_eq!(ek, ek_back);
+    }
+
+    #[test]
+    fn deserialize_rejects_key_of_other_key_switching_method() {
+        let mut rng = thread_rng();
+        let mut params = BfvParameters::new(&[50; 4], 65537, 1 << 4);
+        params.enable_hybrid_key_switching(&[50, 50, 50]);
+        let sk = SecretKey::random(params.degree, params.hw, &mut rng);
+        let ek = EvaluationKey::new(&params, &sk, &[0], &[0], &[1], &mut rng);
+
+        let mut bv_params = params.clone();
+        bv_params
+            .try_set_key_switching_method(KeySwitchingMethod::BV)
+            .unwrap();
+
+        let mut rlk_proto =
+            proto::RelinearizationKey::try_from_with_parameters(&ek.rlks[&0], &params).unwrap();
+        rlk_proto.fingerprint = bv_params.fingerprint().to_vec();
+        assert!(matches!(
+            RelinearizationKey::try_from_with_parameters(&rlk_proto, &bv_params),
+            Err(BfvError::InvalidObject { .. })
+        ));
+
+        let mut rtg_proto =
+            proto::GaloisKey::try_from_with_parameters(&ek.rtgs[&(1, 0)], &params).unwrap();
+        rtg_proto.fingerprint = bv_params.fingerprint().to_vec();
+        assert!(matches!(
+            GaloisKey::try_from_with_parameters(&rtg_proto, &bv_params),
+            Err(BfvError::InvalidObject { .. })
+        ));
+    }
+
+    #[test]
+    fn deserialize_rejects_malformed_protos() {
+        let mut rng = thread_rng();
+        let params = BfvParameters::default(5, 1 << 4);
+        let sk = SecretKey::random_with_params(&params, &mut rng);
+
+        let pk = PublicKey::new(&params, &sk, 0, &mut rng);
+        let pk_proto = proto::PublicKey::try_from_with_parameters(&pk, &params).unwrap();
+        let mut malformed = pk_proto.clone();
+        malformed.c0 = None;
+        assert!(matches!(
+            PublicKey::try_from_with_parameters(&malformed, &params),
+            Err(BfvError::InvalidObject { .. })
+        ));
+        let mut malformed = pk_proto.clone();
+        malformed.seed = Some(vec![0; 16]);
+        assert!(matches!(
+            PublicKey::try_from_with_parameters(&malformed, &params),
+            Err(BfvError::InvalidObject { .. })
+        ));
+        let mut malformed = pk_proto.clone();
+        malformed.level = params.max_level as u32 + 1;
+        assert!(matches!(
+            PublicKey::try_from_with_parameters(&malformed, &params),
+            Err(BfvError::InvalidLevel { .. })
+        ));
+
+        let evaluator = Evaluator::new(params);
+        let params = evaluator.params();
+        let m = params
+            .plaintext_modulus_op
+            .random_vec(params.degree, &mut rng);
+        let ct = evaluator.encrypt(
+            &sk,
+            &evaluator.plaintext_encode(&m, Encoding::default()),
+            &mut rng,
+        );
+        let ct_proto = proto::Ciphertext::try_from_with_parameters(&ct, params).unwrap();
+        let mut malformed = ct_proto.clone();
+        malformed.seed = Some(vec![0; 33]);
+        assert!(matches!(
+            Ciphertext::try_from_with_parameters(&malformed, params),
+            Err(BfvError::InvalidObject { .. })
+        ));
+        let mut malformed = ct_proto.clone();
+        malformed.c.push(malformed.c[0].clone());
+        assert_eq!(
+            Ciphertext::try_from_with_parameters(&malformed, params),
+            Err(BfvError::CiphertextSizeMismatch {
+                expected: 1,
+                found: 2
+            })
+        );
+
+        let ek = EvaluationKey::new(params, &sk, &[0], &[0], &[1], &mut rng);
+        let mut rlk_proto =
+            proto::RelinearizationKey::try_from_with_parameters(&ek.rlks[&0], params).unwrap();
+        match rlk_proto.ksk.as_mut().unwrap() {
+            proto::relinearization_key::Ksk::HybridKsk(ksk) => {
+                ksk.c0s.pop();
+            }
+            proto::relinearization_key::Ksk::BvKsk(ksk) => {
+                ksk.c0s.pop();
+            }
+        }
+        assert!(matches!(
+            RelinearizationKey::try_from_with_parameters(&rlk_proto, params),
+            Err(BfvError::InvalidObject { .. })
+        ));
+        rlk_proto.ksk = None;
+        assert!(matches!(
+            RelinearizationKey::try_from_with_parameters(&rlk_proto, params),
+            Err(BfvError::InvalidObject { .. })
+        ));
+
+        let mut rtg_proto =
+            proto::GaloisKey::try_from_with_parameters(&ek.rtgs[&(1, 0)], params).unwrap();
+        rtg_proto.exponent += 1;
+        assert!(matches!(
+            GaloisKey::try_from_with_parameters(&rtg_proto, params),
+            Err(BfvError::InvalidObject { .. })
+        ));
+    }
 }
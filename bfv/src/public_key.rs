@@ -0,0 +1,138 @@
+use crate::{
+    BfvParameters, Ciphertext, NoiseEstimate, Plaintext, Poly, PolyType, Representation, SecretKey,
+};
+use itertools::Itertools;
+use rand::{CryptoRng, Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct PublicKey {
+    pub(crate) c0: Poly,
+    pub(crate) c1: Poly,
+    pub(crate) seed: Option<<ChaCha8Rng as SeedableRng>::Seed>,
+    pub(crate) level: usize,
+}
+
+impl PublicKey {
+    /// Generates public key for secret key `sk` at given `level`.
+    ///
+    /// Public key is an encryption of zero, ie pk = (e - a*s, a), where `a` is generated
+    /// from a seed.
+    pub fn new<R: CryptoRng + RngCore>(
+        params: &BfvParameters,
+        sk: &SecretKey,
+        level: usize,
+        rng: &mut R,
+    ) -> PublicKey {
+        let ctx = params.poly_ctx(&PolyType::Q, level);
+
+        let mut sk_poly =
+            ctx.try_convert_from_i64_small(&sk.coefficients, Representation::Coefficient);
+        ctx.change_representation(&mut sk_poly, Representation::Evaluation);
+
+        // seed `a`
+        let mut seed = <ChaCha8Rng as SeedableRng>::Seed::default();
+        rng.fill_bytes(&mut seed);
+        let mut a = ctx.random_with_seed(seed);
+        ctx.change_representation(&mut a, Representation::Evaluation);
+
+        // a*sk
+        ctx.mul_assign(&mut sk_poly, &a);
+
+        let mut e = ctx.random_gaussian(Representation::Coefficient, params.variance, rng);
+        ctx.change_representation(&mut e, Representation::Evaluation);
+
+        // e - a*sk
+        ctx.sub_assign(&mut e, &sk_poly);
+
+        // Both c0 and c1 are only used in `Evaluation` representation
+        PublicKey {
+            c0: e,
+            c1: a,
+            seed: Some(seed),
+            level,
+        }
+    }
+
+    /// Encrypts given plaintext with the public key
+    ///
+    /// Panics if plaintext level does not equal public key level
+    pub fn encrypt<R: CryptoRng + RngCore>(
+        &self,
+        params: &BfvParameters,
+        pt: &Plaintext,
+        rng: &mut R,
+    ) -> Ciphertext {
+        let encoding = pt.encoding.as_ref().expect("Plaintext encoding missing!");
+        assert!(encoding.level == self.level);
+
+        let ctx = params.poly_ctx(&PolyType::Q, self.level);
+
+        // u sampled from ternary distribution
+        let u = (0..params.degree)
+            .map(|_| rng.gen_range(-1i64..=1))
+            .collect_vec();
+        let mut u = ctx.try_convert_from_i64_small(&u, Representation::Coefficient);
+        ctx.change_representation(&mut u, Representation::Evaluation);
+
+        let m = pt.scale_plaintext(params, Representation::Evaluation);
+
+        // c0 = pk0*u + e0 + m
+        let mut c0 = ctx.random_gaussian(Representation::Coefficient, params.variance, rng);
+        ctx.change_representation(&mut c0, Representation::Evaluation);
+        ctx.add_assign(&mut c0, &ctx.mul(&self.c0, &u));
+        ctx.add_assign(&mut c0, &m);
+
+        // c1 = pk1*u + e1
+        let mut c1 = ctx.random_gaussian(Representation::Coefficient, params.variance, rng);
+        ctx.change_representation(&mut c1, Representation::Evaluation);
+        ctx.mul_assign(&mut u, &self.c1);
+        ctx.add_assign(&mut c1, &u);
+
+        // Output ciphertext in `Coefficient` representation to match `SecretKey::encrypt`
+        ctx.change_representation(&mut c0, Representation::Coefficient);
+        ctx.change_representation(&mut c1, Representation::Coefficient);
+
+        Ciphertext {
+            c: vec![c0, c1],
+            poly_type: PolyType::Q,
+            level: self.level,
+            seed: None,
+            encoding_type: encoding.encoding_type.clone(),
+            noise: Some(NoiseEstimate::fresh_public(params)),
+        }
+    }
+
+    pub fn level(&self) -> usize {
+        self.level
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Encoding;
+    use rand::thread_rng;
+
+    #[test]
+    fn public_key_encryption_decryption() {
+        let mut rng = thread_rng();
+        let params = BfvParameters::default(3, 1 << 4);
+        let sk = SecretKey::random_with_params(&params, &mut rng);
+
+        for level in [0, 1] {
+            let pk = PublicKey::new(&params, &sk, level, &mut rng);
+
+            let m = params
+                .plaintext_modulus_op
+                .random_vec(params.degree, &mut rng);
+            let pt = Plaintext::encode(&m, &params, Encoding::simd(level, crate::PolyCache::None));
+            let ct = pk.encrypt(&params, &pt, &mut rng);
+
+            let m_back: Vec<u64> = sk
+                .decrypt(&ct, &params)
+                .decode(Encoding::default(), &params);
+            assert_eq!(m, m_back);
+        }
+    }
+}
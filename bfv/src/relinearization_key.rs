@@ -1,72 +1,98 @@
 use crate::{
-    BfvParameters, Ciphertext, HybridKeySwitchingKey, PolyType, Representation, SecretKey,
+    BfvParameters, Ciphertext, KeySwitchingKey, PolyType, QpCiphertext, Representation, SecretKey,
 };
 use rand::{CryptoRng, RngCore};
 
 #[derive(PartialEq, Debug)]
 pub struct RelinearizationKey {
-    pub(crate) ksk: HybridKeySwitchingKey,
+    /// Key switching keys for s^2, s^3, ..., s^max_degree
+    pub(crate) ksks: Vec<KeySwitchingKey>,
     pub(crate) level: usize,
 }
 
 impl RelinearizationKey {
+    /// Generates relinearization key for ciphertexts with 3 polynomials
     pub fn new<R: CryptoRng + RngCore>(
         params: &BfvParameters,
         sk: &SecretKey,
         level: usize,
         rng: &mut R,
     ) -> RelinearizationKey {
+        RelinearizationKey::new_with_max_degree(params, sk, level, 2, rng)
+    }
+
+    /// Generates relinearization key for ciphertexts with upto `max_degree + 1` polynomials
+    ///
+    /// Panics if `max_degree` is less than 2
+    pub fn new_with_max_degree<R: CryptoRng + RngCore>(
+        params: &BfvParameters,
+        sk: &SecretKey,
+        level: usize,
+        max_degree: usize,
+        rng: &mut R,
+    ) -> RelinearizationKey {
+        assert!(max_degree >= 2);
+
         let q_ctx = params.poly_ctx(&PolyType::Q, level);
-        let qp_ctx = params.poly_ctx(&PolyType::QP, level);
 
         let mut sk_poly =
             q_ctx.try_convert_from_i64_small(&sk.coefficients, Representation::Coefficient);
         q_ctx.change_representation(&mut sk_poly, Representation::Evaluation);
 
-        // sk^2
-        let sk_sq = q_ctx.mul(&sk_poly, &sk_poly);
+        // Key switching keys for s^2,...,s^max_degree
+        let mut sk_pow = sk_poly.clone();
+        let ksks = (2..=max_degree)
+            .map(|_| {
+                q_ctx.mul_assign(&mut sk_pow, &sk_poly);
+                KeySwitchingKey::new(params, &sk_pow, sk, level, rng)
+            })
+            .collect();
 
-        // Key switching key
-        let ksk = HybridKeySwitchingKey::new(
-            params.hybrid_key_switching_params_at_level(level),
-            &sk_sq,
-            sk,
-            &qp_ctx,
-            params.variance,
-            rng,
-        );
+        RelinearizationKey { ksks, level }
+    }
 
-        RelinearizationKey { ksk, level }
+    /// Returns max. degree of ciphertext (ie no. of polynomials - 1) that the key can relinearize
+    pub fn max_degree(&self) -> usize {
+        self.ksks.len() + 1
     }
 
+    /// Relinearizes ciphertext with 3 or more polynomials to ciphertext with 2 polynomials
+    ///
+    /// Key switched polynomials of c_2, ..., c_k are accumulated in QP and switched to Q once.
     pub fn relinearize(&self, ct: &Ciphertext, params: &BfvParameters) -> Ciphertext {
-        assert!(ct.c.len() == 3); // otherwise invalid relinerization
+        self.relinearize_qp(ct, params).mod_down(params)
+    }
+
+    /// Same as `relinearize` but returns relinearized ciphertext with key switched polynomials in QP (see
+    /// `QpCiphertext`)
+    pub fn relinearize_qp(&self, ct: &Ciphertext, params: &BfvParameters) -> QpCiphertext {
+        assert!(ct.c.len() >= 3 && ct.c.len() <= self.max_degree() + 1); // otherwise invalid relinerization
         assert!(ct.c[0].representation == Representation::Coefficient);
         assert!(ct.level == self.level);
 
         let level = ct.level;
-        let q_ctx = params.poly_ctx(&PolyType::Q, level);
-        let qp_ctx = params.poly_ctx(&PolyType::QP, level);
-        let specialp_ctx = params.poly_ctx(&PolyType::SpecialP, level);
-
-        let (mut cs0, mut cs1) = self.ksk.switch(
-            params.hybrid_key_switching_params_at_level(self.level),
-            &ct.c[2],
-            &qp_ctx,
-            &q_ctx,
-            &specialp_ctx,
-        );
-        q_ctx.change_representation(&mut cs0, Representation::Coefficient);
-        q_ctx.change_representation(&mut cs1, Representation::Coefficient);
+        let ctx = self.ksks[0].decomposition_ctx(params, level);
 
-        q_ctx.add_assign(&mut cs0, &ct.c[0]);
-        q_ctx.add_assign(&mut cs1, &ct.c[1]);
+        // key switch c_i from s^i to s for i >= 2
+        let (mut d0, mut d1) = self.ksks[0].switch_qp(params, &ct.c[2], level);
+        ct.c.iter()
+            .skip(3)
+            .zip(self.ksks.iter().skip(1))
+            .for_each(|(ci, ksk)| {
+                let (di0, di1) = ksk.switch_qp(params, ci, level);
+                ctx.add_assign(&mut d0, &di0);
+                ctx.add_assign(&mut d1, &di1);
+            });
 
-        Ciphertext {
-            c: vec![cs0, cs1],
-            poly_type: PolyType::Q,
-            level: ct.level,
-            seed: None,
+        QpCiphertext {
+            c: vec![ct.c[0].clone(), ct.c[1].clone()],
+            d: vec![d0, d1],
+            level,
+            method: self.ksks[0].method(),
+            encoding_type: ct.encoding_type.clone(),
+            noise: ct
+                .noise
+                .and_then(|n| n.key_switch(&self.ksks[0], ct.c.len() - 2, params, level)),
         }
     }
 }
@@ -1,7 +1,9 @@
 use crate::plaintext::{Encoding, Plaintext};
-use crate::{BfvParameters, Ciphertext, PolyCache, PolyType};
+use crate::{BfvParameters, Ciphertext, NoiseEstimate, PolyCache, PolyType};
 use crate::{Poly, PolyContext, Representation};
 use itertools::Itertools;
+use num_bigint::BigUint;
+use num_traits::Zero;
 use rand::distributions::{Distribution, Uniform};
 use rand::{CryptoRng, Rng, RngCore, SeedableRng};
 use rand_chacha::ChaCha8Rng;
@@ -135,6 +137,8 @@ impl SecretKey {
             poly_type: PolyType::Q,
             level: encoding.level,
             seed: Some(seed),
+            encoding_type: encoding.encoding_type.clone(),
+            noise: Some(NoiseEstimate::fresh(params)),
         }
     }
 
@@ -145,24 +149,7 @@ impl SecretKey {
 
         let ctx = params.poly_ctx(&ct.poly_type, ct.level);
 
-        let mut m = ct.c[0].clone();
-        ctx.change_representation(&mut m, Representation::Evaluation);
-
-        let s = self.to_poly(&ctx);
-        let mut s_carry = s.clone();
-        for i in 1..ct.c.len() {
-            if ct.c[i].representation == Representation::Evaluation {
-                ctx.add_assign(&mut m, &ctx.mul(&s_carry, &ct.c[i]));
-            } else {
-                let mut tmp = ct.c[i].clone();
-                ctx.change_representation(&mut tmp, Representation::Evaluation);
-                ctx.mul_assign(&mut tmp, &s_carry);
-                ctx.add_assign(&mut m, &tmp);
-            }
-            ctx.mul_assign(&mut s_carry, &s);
-        }
-
-        ctx.change_representation(&mut m, Representation::Coefficient);
+        let m = self.dot_with_powers(ct, &ctx);
         let m = ctx.scale_and_round_decryption(
             &m,
             &params.plaintext_modulus_op,
@@ -172,26 +159,70 @@ impl SecretKey {
             &params.t_ql_hat_inv_modql_divql_frac[ct.level],
             &params.t_bql_hat_inv_modql_divql_frac[ct.level],
         );
+        // record encoding that plaintext should be decoded with
         Plaintext {
             m,
-            encoding: None,
+            encoding: Some(Encoding {
+                encoding_type: ct.encoding_type.clone(),
+                poly_cache: PolyCache::None,
+                level: ct.level,
+            }),
             mul_poly: None,
             add_sub_poly: None,
         }
     }
 
     pub fn measure_noise(&self, ct: &Ciphertext, params: &BfvParameters) -> u64 {
-        // TODO: replace default simd with encoding used for ciphertext. This will require
-        // adding encoding info to ciphertext
-        let m = self.decrypt(ct, params).decode(Encoding::default(), params);
-        let scaled_m = Plaintext::encode(&m, &params, Encoding::simd(ct.level(), PolyCache::None))
-            .scale_plaintext(&params, Representation::Evaluation);
+        // Decrypted plaintext records encoding of the ciphertext and its level, hence can be scaled directly
+        let scaled_m = self
+            .decrypt(ct, params)
+            .scale_plaintext(&params, Representation::Coefficient);
 
         let ctx = params.poly_ctx(&ct.poly_type, ct.level);
 
+        let mut m = self.dot_with_powers(ct, &ctx);
+        ctx.sub_assign(&mut m, &scaled_m);
+
+        let mut noise = 0u64;
+        ctx.try_convert_to_biguint(&m).iter().for_each(|v| {
+            noise = std::cmp::max(noise, std::cmp::min(v.bits(), (ctx.big_q() - v).bits()))
+        });
+        noise
+    }
+
+    /// Returns invariant noise budget of ciphertext in bits.
+    ///
+    /// Invariant noise is v such that t/Ql [c_0 + c_1 s + ... + c_k s^k]_Ql = m + v + at. Decryption succeeds as
+    /// long as ||v|| < 1/2, thus the budget is -log2(2||v||), which is computed as log2(Ql) - log2(||[t c(s)]_Ql||) - 1.
+    /// Returns 0 if budget is exhausted.
+    pub fn noise_budget(&self, ct: &Ciphertext, params: &BfvParameters) -> u64 {
+        assert!(ct.c.len() != 0);
+        assert!(ct.poly_type == PolyType::Q);
+
+        let ctx = params.poly_ctx(&ct.poly_type, ct.level);
+        let big_q = ctx.big_q();
+        let t = BigUint::from(params.plaintext_modulus);
+
+        let mut norm = BigUint::zero();
+        ctx.try_convert_to_biguint(&self.dot_with_powers(ct, &ctx))
+            .iter()
+            .for_each(|v| {
+                let v = (v * &t) % &big_q;
+                let v = std::cmp::min(&big_q - &v, v);
+                if v > norm {
+                    norm = v;
+                }
+            });
+
+        (big_q.bits() as u64).saturating_sub(norm.bits() as u64 + 1)
+    }
+
+    /// Returns c_0 + c_1 s + ... + c_k s^k in coefficient representation
+    fn dot_with_powers(&self, ct: &Ciphertext, ctx: &PolyContext<'_>) -> Poly {
         let mut m = ct.c[0].clone();
         ctx.change_representation(&mut m, Representation::Evaluation);
-        let s = self.to_poly(&ctx);
+
+        let s = self.to_poly(ctx);
         let mut s_carry = s.clone();
         for i in 1..ct.c.len() {
             if ct.c[i].representation == Representation::Evaluation {
@@ -205,14 +236,8 @@ impl SecretKey {
             ctx.mul_assign(&mut s_carry, &s);
         }
 
-        ctx.sub_assign(&mut m, &scaled_m);
         ctx.change_representation(&mut m, Representation::Coefficient);
-
-        let mut noise = 0u64;
-        ctx.try_convert_to_biguint(&m).iter().for_each(|v| {
-            noise = std::cmp::max(noise, std::cmp::min(v.bits(), (ctx.big_q() - v).bits()))
-        });
-        noise
+        m
     }
 }
 
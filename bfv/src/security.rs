@@ -0,0 +1,53 @@
+/// Target security level (classical) of parameters
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum SecurityLevel {
+    Tc128,
+    Tc192,
+    Tc256,
+}
+
+/// Distribution of secret key coefficients
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum SecretDistribution {
+    /// Uniform ternary secret. Expected hamming weight is set to degree/2.
+    Ternary,
+    /// Ternary secret with given hamming weight. `BfvParametersBuilder` refuses hamming weight smaller than
+    /// that of `Ternary` unless insecure parameters are allowed.
+    Sparse(usize),
+}
+
+impl SecretDistribution {
+    /// Returns hamming weight of secret key for given degree
+    pub fn hamming_weight(&self, degree: usize) -> usize {
+        match self {
+            SecretDistribution::Ternary => degree / 2,
+            SecretDistribution::Sparse(hw) => *hw,
+        }
+    }
+}
+
+/// Returns max. log(QP) for `degree` at `security_level` as per Table 1 of HE standard
+/// (https://homomorphicencryption.org/standard/) for ternary secret and error with std. deviation 3.2.
+///
+/// Returns `None` if `degree` is not in the table.
+///
+/// Note that table assumes uniform ternary secret. Sparse secrets with small hamming weight provide lower
+/// security than the table suggests.
+pub fn max_log_qp(degree: usize, security_level: SecurityLevel) -> Option<usize> {
+    // rows: 128, 192, 256 bit security
+    let bounds = match degree {
+        1024 => [27, 19, 14],
+        2048 => [54, 37, 29],
+        4096 => [109, 75, 58],
+        8192 => [218, 152, 118],
+        16384 => [438, 305, 237],
+        32768 => [881, 611, 476],
+        _ => return None,
+    };
+
+    match security_level {
+        SecurityLevel::Tc128 => Some(bounds[0]),
+        SecurityLevel::Tc192 => Some(bounds[1]),
+        SecurityLevel::Tc256 => Some(bounds[2]),
+    }
+}
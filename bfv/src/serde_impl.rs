@@ -0,0 +1,686 @@
+use std::{borrow::Cow, collections::HashMap, marker::PhantomData};
+
+use crate::{
+    convert_bytes_to_ternary, convert_ternary_to_bytes, BVKeySwitchingKey, BfvParameters,
+    Ciphertext, Encoding, EncodingType, EvaluationKey, GaloisKey, HybridKeySwitchingKey,
+    KeySwitchingKey, KeySwitchingMethod, NoiseEstimate, Plaintext, Poly, PolyContext, PolyType,
+    RelinearizationKey, Representation, SecretKey, Substitution,
+};
+use itertools::{izip, Itertools};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{
+    de::{DeserializeSeed, Error},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+type Seed = <ChaCha8Rng as SeedableRng>::Seed;
+
+/// Deserializes values that depend on `BfvParameters`.
+///
+/// Polynomials that can be derived from a seed (for ex, `c[1]` of a fresh ciphertext or `c1s` of key
+/// switching keys) and plaintext's cached polynomials are not serialized. Thus `Ciphertext`,
+/// `Plaintext`, `RelinearizationKey`, `GaloisKey`, and `EvaluationKey` are deserialized with
+/// parameters they were created with using `WithParameters::<T>::new(&params)` as `DeserializeSeed`.
+pub struct WithParameters<'a, T> {
+    params: &'a BfvParameters,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T> WithParameters<'a, T> {
+    pub fn new(params: &'a BfvParameters) -> WithParameters<'a, T> {
+        WithParameters {
+            params,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Returns poly context of `poly_type` at `level`. Returns error if `level` is invalid
+fn poly_ctx<'a, E: Error>(
+    params: &'a BfvParameters,
+    poly_type: &PolyType,
+    level: usize,
+) -> Result<PolyContext<'a>, E> {
+    if level > params.max_level {
+        return Err(E::custom(format!(
+            "level {level} exceeds max level {}",
+            params.max_level
+        )));
+    }
+    Ok(params.poly_ctx(poly_type, level))
+}
+
+/// Returns error if any polynomial does not belong to `ctx` or has coefficient >= qi
+fn check_polys<E: Error>(polys: &[Poly], ctx: &PolyContext<'_>) -> Result<(), E> {
+    if polys
+        .iter()
+        .any(|p| p.coefficients.shape() != [ctx.moduli_count(), ctx.degree()])
+    {
+        return Err(E::custom("polynomial does not match parameters"));
+    }
+    if polys.iter().any(|p| {
+        izip!(p.coefficients.outer_iter(), ctx.iter_moduli_ops())
+            .any(|(xi, modqi)| xi.iter().any(|v| *v >= modqi.modulus()))
+    }) {
+        return Err(E::custom("polynomial coefficient exceeds modulus"));
+    }
+    Ok(())
+}
+
+/// Returns error if key switching method of `params` is not `method`
+fn check_key_switching_method<E: Error>(
+    method: KeySwitchingMethod,
+    params: &BfvParameters,
+) -> Result<(), E> {
+    if params.key_switching_method() != method {
+        return Err(E::custom(format!(
+            "{method:?} key switching key does not match {:?} key switching method of parameters",
+            params.key_switching_method()
+        )));
+    }
+    Ok(())
+}
+
+// Parameters //
+#[derive(Serialize, Deserialize)]
+struct ParametersRepr {
+    degree: usize,
+    plaintext_modulus: u64,
+    ciphertext_moduli: Vec<u64>,
+    extension_moduli: Vec<u64>,
+    special_moduli: Vec<u64>,
+    variance: usize,
+    hw: usize,
+    alpha: usize,
+}
+
+impl Serialize for BfvParameters {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ParametersRepr {
+            degree: self.degree,
+            plaintext_modulus: self.plaintext_modulus,
+            ciphertext_moduli: self.ciphertext_moduli.clone(),
+            extension_moduli: self.extension_moduli.clone(),
+            special_moduli: self.special_moduli.clone().unwrap_or_default(),
+            variance: self.variance,
+            hw: self.hw,
+            alpha: self.alpha.unwrap_or_default(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for BfvParameters {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = ParametersRepr::deserialize(deserializer)?;
+        let mut params = BfvParameters::try_with_moduli(
+            &value.ciphertext_moduli,
+            &value.extension_moduli,
+            &[],
+            value.plaintext_modulus,
+            value.degree,
+        )
+        .map_err(D::Error::custom)?;
+        params.change_variance(value.variance);
+        params.change_hamming_weight(value.hw);
+        if !value.special_moduli.is_empty() {
+            params
+                .try_enable_hybrid_key_switching_with_moduli(&value.special_moduli, value.alpha)
+                .map_err(D::Error::custom)?;
+        }
+        Ok(params)
+    }
+}
+
+// SecretKey //
+#[derive(Serialize, Deserialize)]
+struct SecretKeyRepr {
+    degree: usize,
+    coefficients: Vec<u8>,
+}
+
+impl Serialize for SecretKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SecretKeyRepr {
+            degree: self.coefficients.len(),
+            coefficients: convert_ternary_to_bytes(&self.coefficients),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SecretKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = SecretKeyRepr::deserialize(deserializer)?;
+        let coefficients = convert_bytes_to_ternary(&value.coefficients, value.degree);
+        if coefficients.len() != value.degree {
+            return Err(D::Error::custom("secret key is shorter than degree"));
+        }
+        Ok(SecretKey {
+            coefficients: coefficients.into_boxed_slice(),
+        })
+    }
+}
+
+// Ciphertext //
+#[derive(Serialize, Deserialize)]
+struct CiphertextRepr<'a> {
+    c: Cow<'a, [Poly]>,
+    poly_type: PolyType,
+    level: usize,
+    seed: Option<Seed>,
+    encoding_type: EncodingType,
+    noise: Option<NoiseEstimate>,
+}
+
+impl Serialize for Ciphertext {
+    /// If ciphertext has a seed then `c[1]` is replaced by the seed. Seed is cleared by every operation
+    /// that modifies `c[1]`, thus it is assumed to be valid (see `Ciphertext::has_valid_seed`).
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let seeded = self.seed.is_some()
+            && self.c.len() == 2
+            && self.poly_type == PolyType::Q
+            && self.c[1].representation == Representation::Coefficient;
+        let (c, seed) = if seeded {
+            (&self.c[..1], self.seed)
+        } else {
+            (&self.c[..], None)
+        };
+
+        CiphertextRepr {
+            c: Cow::Borrowed(c),
+            poly_type: self.poly_type.clone(),
+            level: self.level,
+            seed,
+            encoding_type: self.encoding_type.clone(),
+            noise: self.noise,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for WithParameters<'a, Ciphertext> {
+    type Value = Ciphertext;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Ciphertext, D::Error> {
+        let value = CiphertextRepr::deserialize(deserializer)?;
+        if value.poly_type != PolyType::Q && value.poly_type != PolyType::PQ {
+            return Err(D::Error::custom(format!(
+                "ciphertext of poly type {:?} is not supported",
+                value.poly_type
+            )));
+        }
+        let ctx = poly_ctx(self.params, &value.poly_type, value.level)?;
+
+        let mut c = value.c.into_owned();
+        if let Some(seed) = value.seed {
+            if c.len() != 1 || value.poly_type != PolyType::Q {
+                return Err(D::Error::custom(
+                    "seeded ciphertext must have single polynomial of type Q",
+                ));
+            }
+            c.push(ctx.random_with_seed(seed));
+        }
+        check_polys(&c, &ctx)?;
+
+        Ok(Ciphertext {
+            c,
+            poly_type: value.poly_type,
+            level: value.level,
+            seed: value.seed,
+            encoding_type: value.encoding_type,
+            noise: value.noise,
+        })
+    }
+}
+
+// Plaintext //
+#[derive(Serialize, Deserialize)]
+struct PlaintextRepr<'a> {
+    m: Cow<'a, [u64]>,
+    encoding: Option<Encoding>,
+}
+
+impl Serialize for Plaintext {
+    /// Cached polynomials are not serialized. They are recomputed from encoding on deserialization.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PlaintextRepr {
+            m: Cow::Borrowed(&self.m),
+            encoding: self.encoding.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for WithParameters<'a, Plaintext> {
+    type Value = Plaintext;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Plaintext, D::Error> {
+        let value = PlaintextRepr::deserialize(deserializer)?;
+        if value.m.len() != self.params.degree {
+            return Err(D::Error::custom("plaintext length does not match degree"));
+        }
+
+        let m = value.m.into_owned();
+        match value.encoding {
+            Some(encoding) => {
+                // check level is valid before computing cached polynomials
+                poly_ctx::<D::Error>(self.params, &PolyType::Q, encoding.level)?;
+                Ok(Plaintext::with_poly_cache(m, self.params, encoding))
+            }
+            None => Ok(Plaintext {
+                m,
+                encoding: None,
+                mul_poly: None,
+                add_sub_poly: None,
+            }),
+        }
+    }
+}
+
+// Key Switching Key //
+/// Polynomials of key switching keys are stored in `Evaluation` representation. `c1s` are omitted
+/// if key has a seed.
+#[derive(Serialize, Deserialize)]
+enum KeySwitchingKeyRepr<'a> {
+    BV {
+        c0s: Cow<'a, [Poly]>,
+        c1s: Cow<'a, [Poly]>,
+        seed: Option<Seed>,
+    },
+    Hybrid {
+        c0s: Cow<'a, [Poly]>,
+        c1s: Cow<'a, [Poly]>,
+        seed: Option<Seed>,
+    },
+}
+
+impl<'a> From<&'a KeySwitchingKey> for KeySwitchingKeyRepr<'a> {
+    fn from(value: &'a KeySwitchingKey) -> Self {
+        let c1s = |c1s: &'a [Poly], seed: &Option<Seed>| {
+            if seed.is_some() {
+                Cow::Borrowed(&[][..])
+            } else {
+                Cow::Borrowed(c1s)
+            }
+        };
+
+        match value {
+            KeySwitchingKey::BV(ksk) => KeySwitchingKeyRepr::BV {
+                c0s: Cow::Borrowed(&ksk.c0s),
+                c1s: c1s(&ksk.c1s, &ksk.seed),
+                seed: ksk.seed,
+            },
+            KeySwitchingKey::Hybrid(ksk) => KeySwitchingKeyRepr::Hybrid {
+                c0s: Cow::Borrowed(&ksk.c0s),
+                c1s: c1s(&ksk.c1s, &ksk.seed),
+                seed: ksk.seed,
+            },
+        }
+    }
+}
+
+impl KeySwitchingKeyRepr<'_> {
+    fn into_key<E: Error>(
+        self,
+        params: &BfvParameters,
+        level: usize,
+    ) -> Result<KeySwitchingKey, E> {
+        let ksk = match self {
+            KeySwitchingKeyRepr::BV { c0s, c1s, seed } => {
+                check_key_switching_method(KeySwitchingMethod::BV, params)?;
+                let ctx = poly_ctx(params, &PolyType::Q, level)?;
+                let c1s = match seed {
+                    // `generate_c1` returns c1s in `Evaluation` representation
+                    Some(seed) => BVKeySwitchingKey::generate_c1(&ctx, seed),
+                    None => c1s.into_owned(),
+                };
+                check_polys(&c0s, &ctx)?;
+                check_polys(&c1s, &ctx)?;
+                if c0s.len() != ctx.moduli_count() || c1s.len() != c0s.len() {
+                    return Err(E::custom("invalid no. of key switching key polynomials"));
+                }
+
+                KeySwitchingKey::BV(BVKeySwitchingKey {
+                    c0s: c0s.into_owned().into_boxed_slice(),
+                    c1s: c1s.into_boxed_slice(),
+                    seed,
+                })
+            }
+            KeySwitchingKeyRepr::Hybrid { c0s, c1s, seed } => {
+                check_key_switching_method(KeySwitchingMethod::Hybrid, params)?;
+                let ctx = poly_ctx(params, &PolyType::QP, level)?;
+                let dnum = params
+                    .hybrid_ksk_parameters
+                    .as_ref()
+                    .and_then(|p| p.get(level))
+                    .ok_or_else(|| {
+                        E::custom(format!(
+                            "hybrid key switching is not enabled at level {level}"
+                        ))
+                    })?
+                    .dnum;
+                // hybrid key has one c0 (and c1) per digit of decomposition
+                if c0s.len() != dnum {
+                    return Err(E::custom("invalid no. of key switching key polynomials"));
+                }
+                let c1s = match seed {
+                    Some(seed) => {
+                        // `generate_c1` returns c1s in `Coefficient` representation
+                        let mut c1s = HybridKeySwitchingKey::generate_c1(c0s.len(), &ctx, seed);
+                        c1s.iter_mut()
+                            .for_each(|p| ctx.change_representation(p, Representation::Evaluation));
+                        c1s
+                    }
+                    None => c1s.into_owned(),
+                };
+                check_polys(&c0s, &ctx)?;
+                check_polys(&c1s, &ctx)?;
+                if c1s.len() != c0s.len() {
+                    return Err(E::custom("invalid no. of key switching key polynomials"));
+                }
+
+                KeySwitchingKey::Hybrid(HybridKeySwitchingKey {
+                    seed,
+                    c0s: c0s.into_owned().into_boxed_slice(),
+                    c1s: c1s.into_boxed_slice(),
+                })
+            }
+        };
+        Ok(ksk)
+    }
+}
+
+// Relinearization Key //
+#[derive(Serialize, Deserialize)]
+struct RelinearizationKeyRepr<'a> {
+    ksks: Vec<KeySwitchingKeyRepr<'a>>,
+    level: usize,
+}
+
+impl<'a> From<&'a RelinearizationKey> for RelinearizationKeyRepr<'a> {
+    fn from(value: &'a RelinearizationKey) -> Self {
+        RelinearizationKeyRepr {
+            ksks: value.ksks.iter().map(KeySwitchingKeyRepr::from).collect(),
+            level: value.level,
+        }
+    }
+}
+
+impl RelinearizationKeyRepr<'_> {
+    fn into_key<E: Error>(self, params: &BfvParameters) -> Result<RelinearizationKey, E> {
+        if self.ksks.is_empty() {
+            return Err(E::custom("relinearization key has no key switching keys"));
+        }
+        let level = self.level;
+        let ksks = self
+            .ksks
+            .into_iter()
+            .map(|ksk| ksk.into_key(params, level))
+            .collect::<Result<Vec<_>, E>>()?;
+        Ok(RelinearizationKey { ksks, level })
+    }
+}
+
+impl Serialize for RelinearizationKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RelinearizationKeyRepr::from(self).serialize(serializer)
+    }
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for WithParameters<'a, RelinearizationKey> {
+    type Value = RelinearizationKey;
+
+    fn deserialize<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<RelinearizationKey, D::Error> {
+        RelinearizationKeyRepr::deserialize(deserializer)?.into_key(self.params)
+    }
+}
+
+// Galois Key //
+#[derive(Serialize, Deserialize)]
+struct GaloisKeyRepr<'a> {
+    exponent: usize,
+    ksk: KeySwitchingKeyRepr<'a>,
+    level: usize,
+}
+
+impl<'a> From<&'a GaloisKey> for GaloisKeyRepr<'a> {
+    fn from(value: &'a GaloisKey) -> Self {
+        GaloisKeyRepr {
+            exponent: value.substitution.exponent,
+            ksk: KeySwitchingKeyRepr::from(&value.ksk_key),
+            level: value.level,
+        }
+    }
+}
+
+impl GaloisKeyRepr<'_> {
+    fn into_key<E: Error>(self, params: &BfvParameters) -> Result<GaloisKey, E> {
+        if self.exponent & 1 != 1 {
+            return Err(E::custom(format!(
+                "galois exponent {} is not odd",
+                self.exponent
+            )));
+        }
+        Ok(GaloisKey {
+            substitution: Substitution::new(self.exponent, params.degree),
+            ksk_key: self.ksk.into_key(params, self.level)?,
+            level: self.level,
+        })
+    }
+}
+
+impl Serialize for GaloisKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        GaloisKeyRepr::from(self).serialize(serializer)
+    }
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for WithParameters<'a, GaloisKey> {
+    type Value = GaloisKey;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<GaloisKey, D::Error> {
+        GaloisKeyRepr::deserialize(deserializer)?.into_key(self.params)
+    }
+}
+
+// Evaluation Key //
+#[derive(Serialize, Deserialize)]
+struct EvaluationKeyRepr<'a> {
+    rlks: Vec<RelinearizationKeyRepr<'a>>,
+    /// Galois keys with their rotation index
+    rtgs: Vec<(isize, GaloisKeyRepr<'a>)>,
+}
+
+impl Serialize for EvaluationKey {
+    /// Keys are serialized in order of their level and rotation index so that serializing same
+    /// `EvaluationKey` twice produces same output.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let rlks = self
+            .rlks
+            .iter()
+            .sorted_by_key(|(level, _)| **level)
+            .map(|(_, k)| RelinearizationKeyRepr::from(k))
+            .collect_vec();
+        let rtgs = self
+            .rtgs
+            .iter()
+            .sorted_by_key(|(index, _)| **index)
+            .map(|((rot_index, _), k)| (*rot_index, GaloisKeyRepr::from(k)))
+            .collect_vec();
+
+        EvaluationKeyRepr { rlks, rtgs }.serialize(serializer)
+    }
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for WithParameters<'a, EvaluationKey> {
+    type Value = EvaluationKey;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<EvaluationKey, D::Error> {
+        let value = EvaluationKeyRepr::deserialize(deserializer)?;
+
+        let mut rlks = HashMap::new();
+        for rlk in value.rlks {
+            let rlk = rlk.into_key::<D::Error>(self.params)?;
+            rlks.insert(rlk.level, rlk);
+        }
+
+        let mut rtgs = HashMap::new();
+        for (rot_index, rtg) in value.rtgs {
+            let rtg = rtg.into_key::<D::Error>(self.params)?;
+            rtgs.insert((rot_index, rtg.level), rtg);
+        }
+
+        Ok(EvaluationKey { rlks, rtgs })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Evaluator, PolyCache};
+    use bincode::Options;
+    use rand::thread_rng;
+
+    fn serialize<T: Serialize>(value: &T) -> Vec<u8> {
+        bincode::DefaultOptions::new().serialize(value).unwrap()
+    }
+
+    fn deserialize<'a, T>(bytes: &[u8], params: &'a BfvParameters) -> T
+    where
+        for<'de> WithParameters<'a, T>: DeserializeSeed<'de, Value = T>,
+    {
+        bincode::DefaultOptions::new()
+            .deserialize_seed(WithParameters::<T>::new(params), bytes)
+            .unwrap()
+    }
+
+    #[test]
+    fn serde_parameters_and_secret_key() {
+        let mut rng = thread_rng();
+        let mut params = BfvParameters::new(&[50; 5], 65537, 1 << 4);
+        params.enable_hybrid_key_switching_with_alpha(&[50, 50], 2);
+
+        let params_back: BfvParameters = bincode::DefaultOptions::new()
+            .deserialize(&serialize(&params))
+            .unwrap();
+        assert_eq!(params, params_back);
+
+        let sk = SecretKey::random_with_params(&params, &mut rng);
+        let sk_back: SecretKey = bincode::DefaultOptions::new()
+            .deserialize(&serialize(&sk))
+            .unwrap();
+        assert_eq!(sk, sk_back);
+    }
+
+    #[test]
+    fn serde_ciphertext_and_plaintext() {
+        let mut rng = thread_rng();
+        let params = BfvParameters::default(5, 1 << 4);
+        let sk = SecretKey::random_with_params(&params, &mut rng);
+        let m = params
+            .plaintext_modulus_op
+            .random_vec(params.degree, &mut rng);
+        let evaluator = Evaluator::new(params);
+        let params = evaluator.params();
+
+        // fresh ciphertext is stored with seed in place of c[1]
+        let pt = evaluator.plaintext_encode(&m, Encoding::default());
+        let ct = evaluator.encrypt(&sk, &pt, &mut rng);
+        let bytes = serialize(&ct);
+        let ct_back: Ciphertext = deserialize(&bytes, params);
+        assert_eq!(ct, ct_back);
+
+        let ct_full = evaluator.mul_scalar(&ct, 1);
+        assert!(ct_full.seed.is_none());
+        let bytes_full = serialize(&ct_full);
+        assert!(bytes.len() < bytes_full.len());
+        let ct_full_back: Ciphertext = deserialize(&bytes_full, params);
+        assert_eq!(ct_full, ct_full_back);
+
+        // cached polynomials are recomputed
+        let pt = evaluator.plaintext_encode(
+            &m,
+            Encoding::simd(0, PolyCache::All(PolyType::Q, Representation::Coefficient)),
+        );
+        let pt_back: Plaintext = deserialize(&serialize(&pt), params);
+        assert_eq!(pt.m, pt_back.m);
+        assert!(pt.mul_poly == pt_back.mul_poly && pt.add_sub_poly == pt_back.add_sub_poly);
+
+        let pt_dec: Plaintext = deserialize(&serialize(&evaluator.decrypt(&sk, &ct)), params);
+        assert_eq!(evaluator.plaintext_decode(&pt_dec, Encoding::default()), m);
+
+        // invalid level
+        let mut ct_invalid = ct.clone();
+        ct_invalid.level = params.max_level + 1;
+        assert!(bincode::DefaultOptions::new()
+            .deserialize_seed(
+                WithParameters::<Ciphertext>::new(params),
+                &serialize(&ct_invalid)
+            )
+            .is_err());
+
+        // unsupported poly type
+        let mut ct_invalid = ct_full.clone();
+        ct_invalid.poly_type = PolyType::SpecialP;
+        assert!(bincode::DefaultOptions::new()
+            .deserialize_seed(
+                WithParameters::<Ciphertext>::new(params),
+                &serialize(&ct_invalid)
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn serde_evaluation_key() {
+        let mut rng = thread_rng();
+
+        // hybrid and BV key switching
+        let mut hybrid_params = BfvParameters::new(&[50; 5], 65537, 1 << 4);
+        hybrid_params.enable_hybrid_key_switching_with_alpha(&[50, 50], 2);
+        for params in [hybrid_params, BfvParameters::new(&[50; 4], 65537, 1 << 4)] {
+            let sk = SecretKey::random_with_params(&params, &mut rng);
+            let ek = EvaluationKey::new_with_rlk_max_degree(
+                &params,
+                &sk,
+                &[0, 1],
+                3,
+                &[0, 1],
+                &[1, -1],
+                &mut rng,
+            );
+
+            let bytes = serialize(&ek);
+            assert_eq!(bytes, serialize(&ek));
+            let ek_back: EvaluationKey = deserialize(&bytes, &params);
+            assert_eq!(ek, ek_back);
+
+            let rlk = &ek.rlks[&0];
+            let rlk_back: RelinearizationKey = deserialize(&serialize(rlk), &params);
+            assert_eq!(rlk, &rlk_back);
+
+            let rtg = &ek.rtgs[&(1, 0)];
+            let rtg_back: GaloisKey = deserialize(&serialize(rtg), &params);
+            assert_eq!(rtg, &rtg_back);
+        }
+
+        // key of other key switching method is rejected
+        let mut params = BfvParameters::new(&[50; 5], 65537, 1 << 4);
+        params.enable_hybrid_key_switching_with_alpha(&[50, 50], 2);
+        let sk = SecretKey::random_with_params(&params, &mut rng);
+        let ek = EvaluationKey::new(&params, &sk, &[0], &[], &[], &mut rng);
+        params
+            .try_set_key_switching_method(KeySwitchingMethod::BV)
+            .unwrap();
+        assert!(bincode::DefaultOptions::new()
+            .deserialize_seed(
+                WithParameters::<EvaluationKey>::new(&params),
+                &serialize(&ek)
+            )
+            .is_err());
+    }
+}
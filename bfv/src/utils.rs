@@ -29,6 +29,62 @@ pub fn rot_to_galois_element(i: isize, n: usize) -> usize {
     }
 }
 
+/// Returns non-adjacent form (NAF) of `value` as signed powers of two that sum to `value`,
+/// ordered from least to most significant.
+pub fn naf(value: isize) -> Vec<isize> {
+    let mut digits = vec![];
+    let mut v = value;
+    let mut power = 1isize;
+    while v != 0 {
+        if v & 1 == 1 {
+            // digit is 1 if v = 1 mod 4, -1 if v = 3 mod 4
+            let d = 2 - v.rem_euclid(4);
+            digits.push(d * power);
+            v -= d;
+        }
+        v >>= 1;
+        power <<= 1;
+    }
+    digits
+}
+
+/// Returns rotation indices for which galois keys are sufficient to rotate by any amount
+/// using NAF decomposition (see `naf`).
+///
+/// Indices are +-2^i for 2^i < N/4 and N/4, where N is `degree`. Rotating by -N/4 is same as
+/// rotating by N/4, since rows have N/2 slots.
+pub fn power_of_two_rotations(degree: usize) -> Vec<isize> {
+    let row_size = (degree / 2) as isize;
+    let mut indices = vec![];
+    let mut i = 1;
+    while i < row_size / 2 {
+        indices.push(i);
+        indices.push(-i);
+        i <<= 1;
+    }
+    indices.push(row_size / 2);
+    indices
+}
+
+/// Returns rotation indices for which galois keys are required to sum slots in windows of `width`
+/// (see `Evaluator::sum_slots`).
+///
+/// Indices are 2^i for 2^i < min(width, N/2) and row swap (ie 2N - 1) if `width` is N, where N is
+/// `degree`.
+pub fn sum_slots_rotations(degree: usize, width: usize) -> Vec<isize> {
+    let row_size = degree / 2;
+    let mut indices = vec![];
+    let mut i = 1;
+    while i < std::cmp::min(width, row_size) {
+        indices.push(i as isize);
+        i <<= 1;
+    }
+    if width == degree {
+        indices.push((2 * degree - 1) as isize);
+    }
+    indices
+}
+
 pub fn mod_inverse_biguint_u64(a: &BigUint, m: u64) -> BigUint {
     let a_dig = BigUintDig::from_bytes_le(&a.to_bytes_le());
     let m_dig = BigUintDig::from_u64(m).unwrap();
@@ -265,6 +321,19 @@ mod tests {
         dbg!(v);
     }
 
+    #[test]
+    fn naf_works() {
+        for value in -100..100 {
+            let digits = naf(value);
+            assert_eq!(digits.iter().sum::<isize>(), value);
+            // no two adjacent non-zero digits
+            digits.windows(2).for_each(|w| {
+                assert!(w[1].abs() >= 4 * w[0].abs());
+            });
+        }
+        assert_eq!(naf(7), vec![-1, 8]);
+    }
+
     #[test]
     fn convert_to_and_from_bytes() {
         for prime_bits in [17, 43, 50, 59] {